@@ -1,34 +1,55 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
     attr, from_binary, to_binary, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, Empty,
-    QueryRequest, Response, StdError, StdResult, Uint128, WasmMsg, WasmQuery,
+    QueryRequest, Response, StdResult, Uint128, WasmMsg, WasmQuery,
 };
 
 use cw20::Cw20ExecuteMsg;
 use cw20::Cw20ReceiveMsg;
+use lazy_static::lazy_static;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use terra_multi_test::{Contract, ContractWrapper};
-use lazy_static::lazy_static;
-use std::sync::RwLock;
-
 
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::lock::Lock;
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
 
 // This lazy static use allows you the dev to set the aust token addr before you use the anchor mock so that you can mock out AUST as needed.
 lazy_static! {
     // This lazily made static uses a ReadWrite lock to ensure some form of safety on setting/getting values and means you dont need to wrap the code in an unsafe block which looks icky
-    static ref AUST_ADDR_MOCK: RwLock<String> = RwLock::new("string".to_string());
+    static ref AUST_ADDR_MOCK: Lock<String> = Lock::new("string".to_string());
+    // Smallest uusd deposit DepositStable accepts, 0 accepts anything
+    static ref ANCHOR_MIN_DEPOSIT: Lock<Uint128> = Lock::new(Uint128::zero());
 }
 
 // Acquire a write lock on the static value and then update it
 pub fn set_aust_addr(new_addr: String) -> String {
-    let mut addr = AUST_ADDR_MOCK.write().unwrap();
+    let mut addr = AUST_ADDR_MOCK.write();
     *addr = new_addr;
     return addr.to_string();
 }
 
 pub fn get_aust_addr() -> String {
-    return AUST_ADDR_MOCK.read().unwrap().to_string();
+    return AUST_ADDR_MOCK.read().to_string();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_anchor_min_deposit(new_minimum: Uint128) -> Uint128 {
+    let mut minimum = ANCHOR_MIN_DEPOSIT.write();
+    *minimum = new_minimum;
+    return *minimum;
+}
+
+pub fn get_anchor_min_deposit() -> Uint128 {
+    return *ANCHOR_MIN_DEPOSIT.read();
+}
+
+// Put the statics back to the defaults above, see reset_mock_state
+pub(crate) fn reset_state() {
+    set_aust_addr("string".to_string());
+    set_anchor_min_deposit(Uint128::zero());
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -58,6 +79,7 @@ pub enum AnchorQuery {
         block_height: Option<u64>,
         distributed_interest: Option<Uint256>,
     },
+    Version {},
 }
 
 // The response that should be returned by the EpochState query
@@ -94,6 +116,7 @@ pub enum Cw20HookMsg {
 pub fn contract_anchor_mock() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
         |deps, _, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
             match msg {
                 MockExecuteMsg::Receive(Cw20ReceiveMsg {
                     sender: _,
@@ -130,7 +153,7 @@ pub fn contract_anchor_mock() -> Box<dyn Contract<Empty>> {
                                 attr("redeem_amount", redeem_amount),
                             ]))
                     }
-                    _ => Err(StdError::generic_err("Unauthorized")),
+                    _ => Err(MockError::Unauthorized {}.into()),
                 },
                 MockExecuteMsg::DepositStable {} => {
                     // Check base denom deposit
@@ -140,6 +163,14 @@ pub fn contract_anchor_mock() -> Box<dyn Contract<Empty>> {
                         .find(|c| c.denom == String::from("uusd"))
                         .map(|c| Uint256::from(c.amount))
                         .unwrap_or_else(Uint256::zero);
+                    let minimum = get_anchor_min_deposit();
+                    if deposit_amount < Uint256::from(minimum) {
+                        return Err(MockError::DepositBelowMinimum {
+                            deposit: deposit_amount.to_string(),
+                            minimum: minimum.to_string(),
+                        }
+                        .into());
+                    }
                     // Get Mint amount
                     let mint_amount = deposit_amount / Decimal256::percent(120);
                     // Perform a mint from the contract
@@ -199,15 +230,23 @@ pub fn contract_anchor_mock() -> Box<dyn Contract<Empty>> {
             }
         },
         |_, _, _, _: MockInstantiateMsg| -> StdResult<Response> { Ok(Response::default()) },
-        |_, _, msg: AnchorQuery| -> StdResult<Binary> {
+        |deps, _, msg: AnchorQuery| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
             match msg {
                 AnchorQuery::EpochState {
                     distributed_interest: _,
                     block_height: _,
                 } => Ok(to_binary(&mock_epoch_state())?),
+                AnchorQuery::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
             }
         },
-    );
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
     Box::new(contract)
 }
 
@@ -0,0 +1,230 @@
+// Assertion helpers for consumer tests, these panic with a readable message like assert_eq! does
+#[cfg(feature = "terraswap")]
+use crate::helpers::{tracked_balances, QueryExt};
+#[cfg(feature = "terraswap")]
+use crate::terraswap_mock::{MockQueryMsg, PoolResponse};
+#[cfg(feature = "terraswap")]
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{from_binary, to_binary};
+#[cfg(feature = "terraswap")]
+use cosmwasm_std::{Addr, Uint128};
+#[cfg(feature = "terraswap")]
+use cw20::TokenInfoResponse;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+#[cfg(feature = "terraswap")]
+use terra_multi_test::App;
+use terra_multi_test::AppResponse;
+
+// Check `actual` is within `tolerance_bps` basis points of `expected`, relative to `expected`
+#[cfg(feature = "terraswap")]
+fn within_bps(actual: Uint128, expected: Uint128, tolerance_bps: u16) -> bool {
+    let diff = if actual > expected {
+        actual - expected
+    } else {
+        expected - actual
+    };
+    diff.u128() * 10_000 <= expected.u128() * tolerance_bps as u128
+}
+
+// Assert two pools hold the same assets with reserves and total_share within `tolerance_bps` basis points
+// of the expected values, so rounding in multi-step swap simulations doesn't make tests flaky
+#[cfg(feature = "terraswap")]
+pub fn assert_pool_approx_eq(actual: &PoolResponse, expected: &PoolResponse, tolerance_bps: u16) {
+    let mut mismatches: Vec<String> = vec![];
+    for (actual_asset, expected_asset) in actual.assets.iter().zip(expected.assets.iter()) {
+        if actual_asset.info != expected_asset.info {
+            mismatches.push(format!(
+                "asset info: actual {} != expected {}",
+                actual_asset.info, expected_asset.info
+            ));
+        } else if !within_bps(actual_asset.amount, expected_asset.amount, tolerance_bps) {
+            mismatches.push(format!(
+                "{} reserve: actual {} vs expected {}",
+                expected_asset.info, actual_asset.amount, expected_asset.amount
+            ));
+        }
+    }
+    if !within_bps(actual.total_share, expected.total_share, tolerance_bps) {
+        mismatches.push(format!(
+            "total_share: actual {} vs expected {}",
+            actual.total_share, expected.total_share
+        ));
+    }
+    if !mismatches.is_empty() {
+        panic!(
+            "pools differ by more than {} bps:\n  {}",
+            tolerance_bps,
+            mismatches.join("\n  ")
+        );
+    }
+}
+
+// Assert the constant product of the reserves didn't go down between two pools, e.g. around a sequence of swaps.
+// The commission stays in the pool so every swap should grow k a little, a drop means the swap math paid out too much.
+#[cfg(feature = "terraswap")]
+pub fn assert_k_preserved(before: &PoolResponse, after: &PoolResponse) {
+    let k_before = Uint256::from(before.assets[0].amount) * Uint256::from(before.assets[1].amount);
+    let k_after = Uint256::from(after.assets[0].amount) * Uint256::from(after.assets[1].amount);
+    assert!(
+        k_after >= k_before,
+        "constant product decreased: before {} ({}, {}) > after {} ({}, {})",
+        k_before,
+        before.assets[0],
+        before.assets[1],
+        k_after,
+        after.assets[0],
+        after.assets[1]
+    );
+}
+
+// Assert the balances of every account the terraswap mock token tracks add up to its TokenInfo total_supply.
+// Accounts are paged through with AllAccounts, so only balances credited by mints and transfers are counted.
+// With a rebase factor each balance is rounded down on its own, keep the factor at one for exact checks.
+#[cfg(feature = "terraswap")]
+pub fn assert_supply_consistent(app: &App, token: &Addr) {
+    let mut total = Uint128::zero();
+    for (_, balance) in tracked_balances(app, token) {
+        total = total
+            .checked_add(balance)
+            .expect("sum of balances overflowed");
+    }
+    let token_info: TokenInfoResponse = app
+        .query_mock(token, &MockQueryMsg::TokenInfo {})
+        .expect("TokenInfo query failed");
+    assert_eq!(
+        total, token_info.total_supply,
+        "sum of balances {} != total_supply {} of token {}",
+        total, token_info.total_supply, token
+    );
+}
+
+// The value of the first attribute called `key` across all the events of the response.
+// The wasm events carry the attributes the contracts added, e.g. return_amount of a swap.
+pub fn attribute_value(res: &AppResponse, key: &str) -> Option<String> {
+    res.events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key == key)
+        .map(|attribute| attribute.value.clone())
+}
+
+// Assert the response emitted an attribute called `key` with the expected value
+pub fn assert_attribute(res: &AppResponse, key: &str, expected: &str) {
+    match attribute_value(res, key) {
+        Some(value) => assert_eq!(
+            value, expected,
+            "attribute {}: actual {} != expected {}",
+            key, value, expected
+        ),
+        None => panic!("no attribute {} in the response events", key),
+    }
+}
+
+// Assert one event of type `ty` (e.g. "wasm") carries every one of the key/value pairs, other attributes are ignored
+pub fn assert_event(res: &AppResponse, ty: &str, attrs: &[(&str, &str)]) {
+    let events: Vec<_> = res.events.iter().filter(|event| event.ty == ty).collect();
+    let matched = events.iter().any(|event| {
+        attrs.iter().all(|(key, value)| {
+            event
+                .attributes
+                .iter()
+                .any(|attribute| attribute.key == *key && attribute.value == *value)
+        })
+    });
+    if !matched {
+        panic!(
+            "no {} event with attributes {:?}, the {} events were:\n  {}",
+            ty,
+            attrs,
+            ty,
+            events
+                .iter()
+                .map(|event| format!("{:?}", event.attributes))
+                .collect::<Vec<_>>()
+                .join("\n  ")
+        );
+    }
+}
+
+// Assert the message survives a JSON round trip unchanged, pins the wire format of a message type so a field rename
+// or a changed serde attribute that would break contracts built against the old schema shows up in tests
+pub fn assert_msg_roundtrips<T>(msg: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = to_binary(&msg).expect("message failed to serialize");
+    let decoded: T = from_binary(&json).unwrap_or_else(|err| {
+        panic!(
+            "message failed to deserialize from {}: {}",
+            String::from_utf8_lossy(json.as_slice()),
+            err
+        )
+    });
+    assert_eq!(
+        decoded,
+        msg,
+        "message changed in a JSON round trip through {}",
+        String::from_utf8_lossy(json.as_slice())
+    );
+}
+
+#[cfg(all(test, feature = "terraswap"))]
+mod tests {
+    use super::*;
+    use crate::assets::{asset, native};
+
+    fn pool(token: u128, uusd: u128, total_share: u128) -> PoolResponse {
+        PoolResponse {
+            assets: [
+                asset(native("token"), Uint128::new(token)),
+                asset(native("uusd"), Uint128::new(uusd)),
+            ],
+            total_share: Uint128::new(total_share),
+        }
+    }
+
+    #[test]
+    fn pools_within_tolerance_pass() {
+        // 10 bps of 10000 is 10 either way
+        assert_pool_approx_eq(
+            &pool(10_010, 9_990, 1_001),
+            &pool(10_000, 10_000, 1_000),
+            10,
+        );
+        assert_pool_approx_eq(
+            &pool(10_000, 10_000, 1_000),
+            &pool(10_000, 10_000, 1_000),
+            0,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "uusd reserve: actual 9989 vs expected 10000")]
+    fn reserve_outside_tolerance_panics() {
+        assert_pool_approx_eq(
+            &pool(10_000, 9_989, 1_000),
+            &pool(10_000, 10_000, 1_000),
+            10,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "total_share: actual 1002 vs expected 1000")]
+    fn total_share_outside_tolerance_panics() {
+        assert_pool_approx_eq(
+            &pool(10_000, 10_000, 1_002),
+            &pool(10_000, 10_000, 1_000),
+            10,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "asset info")]
+    fn different_assets_panic() {
+        let mut other = pool(10_000, 10_000, 1_000);
+        other.assets[0].info = native("uluna");
+        assert_pool_approx_eq(&other, &pool(10_000, 10_000, 1_000), 10_000);
+    }
+}
@@ -0,0 +1,49 @@
+// Short constructors for the terraswap asset types, so tests don't spell out AssetInfo literals everywhere
+//
+// let offer = asset(native("uusd"), Uint128::new(1_000));
+// let pair_assets = [token("contract0"), native("uusd")];
+use cosmwasm_std::Uint128;
+use terraswap::asset::{Asset, AssetInfo};
+
+/// A native coin, e.g. native("uusd")
+///
+/// ```
+/// use cw_terra_test_mocks::prelude::native;
+/// use terraswap::asset::AssetInfo;
+///
+/// assert_eq!(native("uusd"), AssetInfo::NativeToken { denom: "uusd".to_string() });
+/// ```
+pub fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+/// A cw20 token, by contract address
+///
+/// ```
+/// use cw_terra_test_mocks::prelude::token;
+/// use terraswap::asset::AssetInfo;
+///
+/// assert_eq!(
+///     token("contract0"),
+///     AssetInfo::Token { contract_addr: "contract0".to_string() }
+/// );
+/// ```
+pub fn token(addr: &str) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: addr.to_string(),
+    }
+}
+
+/// ```
+/// use cosmwasm_std::Uint128;
+/// use cw_terra_test_mocks::prelude::{asset, native};
+/// use terraswap::asset::Asset;
+///
+/// let offer = asset(native("uusd"), Uint128::new(1_000));
+/// assert_eq!(offer, Asset { info: native("uusd"), amount: Uint128::new(1_000) });
+/// ```
+pub fn asset(info: AssetInfo, amount: Uint128) -> Asset {
+    Asset { info, amount }
+}
@@ -0,0 +1,62 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Describes which mocks this build of the crate was compiled with so consumer test setup can branch on it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CapabilitiesResponse {
+    pub version: String,
+    pub terraswap: bool,
+    pub anchor: bool,
+    pub oracle: bool,
+    pub ownable: bool,
+    pub staking: bool,
+    pub cw1: bool,
+    pub cw3: bool,
+    pub cw4: bool,
+    pub cw721: bool,
+    pub router: bool,
+    pub lsd: bool,
+    pub factory: bool,
+    pub trace: bool,
+    // The execute messages the terraswap mock answers, in snake_case as they appear on the wire
+    pub terraswap_messages: Vec<String>,
+}
+
+// Return the capabilities of this build, populated from the enabled cargo features
+pub fn mock_capabilities() -> CapabilitiesResponse {
+    let resp: CapabilitiesResponse = CapabilitiesResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        terraswap: cfg!(feature = "terraswap"),
+        anchor: cfg!(feature = "anchor"),
+        oracle: cfg!(feature = "oracle"),
+        ownable: cfg!(feature = "ownable"),
+        staking: cfg!(feature = "staking"),
+        cw1: cfg!(feature = "cw1"),
+        cw3: cfg!(feature = "cw3"),
+        cw4: cfg!(feature = "cw4"),
+        cw721: cfg!(feature = "cw721"),
+        router: cfg!(feature = "router"),
+        lsd: cfg!(feature = "lsd"),
+        factory: cfg!(feature = "factory"),
+        trace: cfg!(feature = "trace"),
+        terraswap_messages: vec![
+            "receive",
+            "mint",
+            "send",
+            "burn",
+            "transfer",
+            "transfer_from",
+            "freeze_account",
+            "unfreeze_account",
+            "provide_liquidity",
+            "withdraw_liquidity",
+            "swap",
+            "rebase",
+            "reset_volume",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect(),
+    };
+    return resp;
+}
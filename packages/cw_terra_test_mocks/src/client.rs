@@ -0,0 +1,47 @@
+use cosmwasm_std::{Addr, StdResult};
+use cw20::BalanceResponse;
+use serde::de::DeserializeOwned;
+use terra_multi_test::App;
+use terraswap::asset::Asset;
+use terraswap::pair::SimulationResponse;
+
+use crate::helpers::QueryExt;
+use crate::terraswap_mock::{MockQueryMsg, PairResponse, PoolResponse};
+
+// Typed queries against a terraswap mock in an App, each method pairs the query message with its response type
+// so a test can't ask for a PoolResponse from the Pair query by mistake
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerraswapMockClient {
+    pub addr: Addr,
+}
+
+impl TerraswapMockClient {
+    pub fn new(addr: Addr) -> Self {
+        TerraswapMockClient { addr }
+    }
+
+    fn query<T: DeserializeOwned>(&self, app: &App, msg: &MockQueryMsg) -> StdResult<T> {
+        app.query_mock(&self.addr, msg)
+    }
+
+    pub fn pool(&self, app: &App) -> StdResult<PoolResponse> {
+        self.query(app, &MockQueryMsg::Pool {})
+    }
+
+    pub fn pair(&self, app: &App) -> StdResult<PairResponse> {
+        self.query(app, &MockQueryMsg::Pair { asset_infos: None })
+    }
+
+    pub fn simulate(&self, app: &App, offer_asset: Asset) -> StdResult<SimulationResponse> {
+        self.query(app, &MockQueryMsg::Simulation { offer_asset })
+    }
+
+    pub fn balance(&self, app: &App, address: impl Into<String>) -> StdResult<BalanceResponse> {
+        self.query(
+            app,
+            &MockQueryMsg::Balance {
+                address: address.into(),
+            },
+        )
+    }
+}
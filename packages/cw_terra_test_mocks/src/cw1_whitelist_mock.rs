@@ -0,0 +1,137 @@
+use cosmwasm_std::{to_binary, Addr, Binary, CosmosMsg, Empty, Response, StdResult};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::validation::validate_addr;
+
+// Mocked instantiate, the admins allowed to execute through the proxy and whether they can be changed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    pub admins: Vec<String>,
+    pub mutable: bool,
+}
+
+// Slimmed down cw1-whitelist ExecuteMsg
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    /// Forward the messages from the proxy if the sender is an admin
+    Execute { msgs: Vec<CosmosMsg<Empty>> },
+    /// Stop the admin list from ever changing again
+    Freeze {},
+    /// Replace the admin list, only while it's still mutable
+    UpdateAdmins { admins: Vec<String> },
+}
+
+// Mocked Query handler, only the AdminList query is needed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    AdminList {},
+    Version {},
+}
+
+// The response that should be returned by the AdminList query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminListResponse {
+    pub admins: Vec<String>,
+    pub mutable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminList {
+    pub admins: Vec<Addr>,
+    pub mutable: bool,
+}
+
+impl AdminList {
+    pub fn is_admin(&self, addr: &Addr) -> bool {
+        self.admins.iter().any(|admin| admin == addr)
+    }
+
+    pub fn can_modify(&self, addr: &Addr) -> bool {
+        self.mutable && self.is_admin(addr)
+    }
+}
+
+pub const ADMIN_LIST: Item<AdminList> = Item::new("admin_list");
+
+pub fn contract_cw1_whitelist_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, _, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            let mut cfg = ADMIN_LIST.load(deps.storage)?;
+            match msg {
+                MockExecuteMsg::Execute { msgs } => {
+                    if !cfg.is_admin(&info.sender) {
+                        return Err(MockError::Unauthorized {}.into());
+                    }
+                    Ok(Response::new()
+                        .add_messages(msgs)
+                        .add_attribute("action", "execute")
+                        .add_attribute("owner", info.sender))
+                }
+                MockExecuteMsg::Freeze {} => {
+                    if !cfg.can_modify(&info.sender) {
+                        return Err(MockError::Unauthorized {}.into());
+                    }
+                    cfg.mutable = false;
+                    ADMIN_LIST.save(deps.storage, &cfg)?;
+                    Ok(Response::new().add_attribute("action", "freeze"))
+                }
+                MockExecuteMsg::UpdateAdmins { admins } => {
+                    if !cfg.can_modify(&info.sender) {
+                        return Err(MockError::Unauthorized {}.into());
+                    }
+                    cfg.admins = admins
+                        .iter()
+                        .map(|admin| validate_addr(deps.api, admin))
+                        .collect::<StdResult<Vec<Addr>>>()?;
+                    ADMIN_LIST.save(deps.storage, &cfg)?;
+                    Ok(Response::new().add_attribute("action", "update_admins"))
+                }
+            }
+        },
+        |deps, _, _, msg: MockInstantiateMsg| -> StdResult<Response> {
+            let admins = msg
+                .admins
+                .iter()
+                .map(|admin| validate_addr(deps.api, admin))
+                .collect::<StdResult<Vec<Addr>>>()?;
+            ADMIN_LIST.save(
+                deps.storage,
+                &AdminList {
+                    admins,
+                    mutable: msg.mutable,
+                },
+            )?;
+            Ok(Response::default())
+        },
+        |deps, _, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::AdminList {} => {
+                    let cfg = ADMIN_LIST.load(deps.storage)?;
+                    Ok(to_binary(&AdminListResponse {
+                        admins: cfg.admins.into_iter().map(String::from).collect(),
+                        mutable: cfg.mutable,
+                    })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
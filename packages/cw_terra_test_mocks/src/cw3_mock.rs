@@ -0,0 +1,317 @@
+use cosmwasm_std::{
+    to_binary, Addr, Binary, BlockInfo, CosmosMsg, Decimal, Empty, Response, StdResult, Uint128,
+};
+use cw0::{Duration, Expiration};
+use cw3::{Status, Vote};
+use cw_storage_plus::{Item, Map, U64Key};
+use lazy_static::lazy_static;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::lock::Lock;
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::validation::validate_addr;
+
+// Same precision trick cw-utils uses so percentages of small weights round up correctly
+const PRECISION_FACTOR: u128 = 1_000_000_000;
+
+// The passing rules a cw3 contract can be configured with, in the same shape as cw-utils' Threshold
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Threshold {
+    // A proposal passes once the yes weight reaches `weight`
+    AbsoluteCount { weight: u64 },
+    // A proposal passes once the yes weight reaches `percentage` of the total weight
+    AbsolutePercentage { percentage: Decimal },
+    // A proposal passes once `quorum` of the total weight has voted and `threshold` of the non-abstaining votes are yes
+    ThresholdQuorum { threshold: Decimal, quorum: Decimal },
+}
+
+impl Threshold {
+    pub fn is_passed(&self, proposal: &Proposal, total_weight: u64) -> bool {
+        match self {
+            Threshold::AbsoluteCount { weight } => proposal.yes_weight >= *weight,
+            Threshold::AbsolutePercentage { percentage } => {
+                proposal.yes_weight >= votes_needed(total_weight, *percentage)
+            }
+            Threshold::ThresholdQuorum { threshold, quorum } => {
+                let total_votes =
+                    proposal.yes_weight + proposal.no_weight + proposal.abstain_weight;
+                total_votes >= votes_needed(total_weight, *quorum)
+                    && proposal.yes_weight
+                        >= votes_needed(total_votes - proposal.abstain_weight, *threshold)
+            }
+        }
+    }
+}
+
+// Weight needed to reach the given percentage of `weight`, rounded up
+fn votes_needed(weight: u64, percentage: Decimal) -> u64 {
+    let applied = Uint128::new(PRECISION_FACTOR * weight as u128) * percentage;
+    ((applied.u128() + PRECISION_FACTOR - 1) / PRECISION_FACTOR) as u64
+}
+
+// This lazy static use allows you the dev to override the passing rule of the cw3 mock before proposals are voted on.
+lazy_static! {
+    // When unset the mock uses an absolute count of the required_weight given at instantiation
+    static ref CW3_THRESHOLD: Lock<Option<Threshold>> = Lock::new(None);
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_cw3_threshold(threshold: Threshold) -> Threshold {
+    let mut current = CW3_THRESHOLD.write();
+    *current = Some(threshold.clone());
+    return threshold;
+}
+
+// Go back to the required_weight given at instantiation
+pub fn clear_cw3_threshold() {
+    *CW3_THRESHOLD.write() = None;
+}
+
+pub fn get_cw3_threshold() -> Option<Threshold> {
+    return CW3_THRESHOLD.read().clone();
+}
+
+// Put the statics back to the defaults above, see reset_mock_state
+pub(crate) fn reset_state() {
+    clear_cw3_threshold();
+}
+
+// A voter and their weight, given at instantiation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct Voter {
+    pub addr: String,
+    pub weight: u64,
+}
+
+// Mocked instantiate, a fixed set of voters, the yes weight needed to pass and how long proposals stay open
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    pub voters: Vec<Voter>,
+    pub required_weight: u64,
+    pub max_voting_period: Duration,
+}
+
+// Slimmed down cw3 ExecuteMsg with the propose -> vote -> execute flow
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg<Empty>>,
+    },
+    Vote {
+        proposal_id: u64,
+        vote: Vote,
+    },
+    Execute {
+        proposal_id: u64,
+    },
+}
+
+// Mocked Query handler, only the Proposal query is needed to check on the status of a proposal
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    Proposal { proposal_id: u64 },
+    Version {},
+}
+
+// The response that should be returned by the Proposal query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub status: Status,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub required_weight: u64,
+    pub total_weight: u64,
+    pub max_voting_period: Duration,
+}
+
+impl Config {
+    // The threshold set with set_cw3_threshold wins over the one from instantiation
+    pub fn threshold(&self) -> Threshold {
+        get_cw3_threshold().unwrap_or(Threshold::AbsoluteCount {
+            weight: self.required_weight,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Proposal {
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub status: Status,
+    pub expires: Expiration,
+    pub yes_weight: u64,
+    // No and Veto votes both count against the proposal
+    pub no_weight: u64,
+    pub abstain_weight: u64,
+}
+
+impl Proposal {
+    // Open or passed proposals that reach their expiry without being executed count as rejected
+    pub fn current_status(&self, block: &BlockInfo) -> Status {
+        match self.status {
+            Status::Open | Status::Passed if self.expires.is_expired(block) => Status::Rejected,
+            _ => self.status,
+        }
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const VOTERS: Map<&Addr, u64> = Map::new("voters");
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+pub const PROPOSALS: Map<U64Key, Proposal> = Map::new("proposals");
+pub const BALLOTS: Map<(U64Key, &Addr), Vote> = Map::new("ballots");
+
+pub fn contract_cw3_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, env, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            // Only voters can interact with proposals
+            let weight = VOTERS
+                .may_load(deps.storage, &info.sender)?
+                .ok_or(MockError::Unauthorized {})?;
+            match msg {
+                MockExecuteMsg::Propose {
+                    title,
+                    description,
+                    msgs,
+                } => {
+                    let cfg = CONFIG.load(deps.storage)?;
+                    // The proposer automatically votes yes with their weight
+                    let mut proposal = Proposal {
+                        title,
+                        description,
+                        msgs,
+                        status: Status::Open,
+                        expires: cfg.max_voting_period.after(&env.block),
+                        yes_weight: weight,
+                        no_weight: 0,
+                        abstain_weight: 0,
+                    };
+                    if cfg.threshold().is_passed(&proposal, cfg.total_weight) {
+                        proposal.status = Status::Passed;
+                    }
+                    let id = PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+                    PROPOSAL_COUNT.save(deps.storage, &id)?;
+                    PROPOSALS.save(deps.storage, id.into(), &proposal)?;
+                    BALLOTS.save(deps.storage, (id.into(), &info.sender), &Vote::Yes)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "propose")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("proposal_id", id.to_string())
+                        .add_attribute("status", format!("{:?}", proposal.status)))
+                }
+                MockExecuteMsg::Vote { proposal_id, vote } => {
+                    let cfg = CONFIG.load(deps.storage)?;
+                    let mut proposal = PROPOSALS.load(deps.storage, proposal_id.into())?;
+                    if proposal.status != Status::Open {
+                        return Err(MockError::ProposalNotOpen {}.into());
+                    }
+                    if proposal.expires.is_expired(&env.block) {
+                        return Err(MockError::VotingPeriodExpired {}.into());
+                    }
+                    BALLOTS.update(
+                        deps.storage,
+                        (proposal_id.into(), &info.sender),
+                        |ballot| -> StdResult<_> {
+                            match ballot {
+                                Some(_) => Err(MockError::AlreadyVoted {}.into()),
+                                None => Ok(vote),
+                            }
+                        },
+                    )?;
+                    match vote {
+                        Vote::Yes => proposal.yes_weight += weight,
+                        Vote::No | Vote::Veto => proposal.no_weight += weight,
+                        Vote::Abstain => proposal.abstain_weight += weight,
+                    }
+                    if cfg.threshold().is_passed(&proposal, cfg.total_weight) {
+                        proposal.status = Status::Passed;
+                    }
+                    PROPOSALS.save(deps.storage, proposal_id.into(), &proposal)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "vote")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("proposal_id", proposal_id.to_string())
+                        .add_attribute("status", format!("{:?}", proposal.status)))
+                }
+                MockExecuteMsg::Execute { proposal_id } => {
+                    let mut proposal = PROPOSALS.load(deps.storage, proposal_id.into())?;
+                    if proposal.status == Status::Passed && proposal.expires.is_expired(&env.block)
+                    {
+                        return Err(MockError::ProposalExpired {}.into());
+                    }
+                    if proposal.status != Status::Passed {
+                        return Err(MockError::ProposalNotPassed {}.into());
+                    }
+                    proposal.status = Status::Executed;
+                    PROPOSALS.save(deps.storage, proposal_id.into(), &proposal)?;
+                    Ok(Response::new()
+                        .add_messages(proposal.msgs)
+                        .add_attribute("action", "execute")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("proposal_id", proposal_id.to_string()))
+                }
+            }
+        },
+        |deps, _, _, msg: MockInstantiateMsg| -> StdResult<Response> {
+            let mut total_weight = 0;
+            for voter in msg.voters.iter() {
+                let voter_addr = validate_addr(deps.api, &voter.addr)?;
+                VOTERS.save(deps.storage, &voter_addr, &voter.weight)?;
+                total_weight += voter.weight;
+            }
+            CONFIG.save(
+                deps.storage,
+                &Config {
+                    required_weight: msg.required_weight,
+                    total_weight,
+                    max_voting_period: msg.max_voting_period,
+                },
+            )?;
+            Ok(Response::default())
+        },
+        |deps, env, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::Proposal { proposal_id } => {
+                    let proposal = PROPOSALS.load(deps.storage, proposal_id.into())?;
+                    let status = proposal.current_status(&env.block);
+                    Ok(to_binary(&ProposalResponse {
+                        id: proposal_id,
+                        title: proposal.title,
+                        description: proposal.description,
+                        msgs: proposal.msgs,
+                        status,
+                        expires: proposal.expires,
+                    })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
@@ -0,0 +1,143 @@
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Empty, Order, OverflowError, OverflowOperation, Response, StdError,
+    StdResult,
+};
+use cw4::{Member, MemberListResponse, MemberResponse, TotalWeightResponse};
+use cw_storage_plus::{Bound, Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::validation::validate_addr;
+
+// Same pagination limits cw4-group uses so ListMembers behaves like the real thing
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// Simple mocked instantiate with no params, members are added afterwards via UpdateMembers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {}
+
+// Slimmed down cw4-group ExecuteMsg, only membership updates are mocked
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    UpdateMembers {
+        remove: Vec<String>,
+        add: Vec<Member>,
+    },
+}
+
+// Mocked Query handler with the cw4 queries membership-gated contracts rely on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    Member {
+        addr: String,
+    },
+    ListMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    TotalWeight {},
+    Version {},
+}
+
+pub const MEMBERS: Map<&Addr, u64> = Map::new("members");
+pub const TOTAL: Item<u64> = Item::new("total");
+
+// The weights are u64 like cw4's, their checked math fails with the same overflow error as Uint128's does
+fn checked_add(total: u64, weight: u64) -> StdResult<u64> {
+    total.checked_add(weight).ok_or_else(|| {
+        StdError::overflow(OverflowError::new(OverflowOperation::Add, total, weight))
+    })
+}
+
+fn checked_sub(total: u64, weight: u64) -> StdResult<u64> {
+    total.checked_sub(weight).ok_or_else(|| {
+        StdError::overflow(OverflowError::new(OverflowOperation::Sub, total, weight))
+    })
+}
+
+pub fn contract_cw4_group_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, _, _, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            match msg {
+                MockExecuteMsg::UpdateMembers { remove, add } => {
+                    let mut total = TOTAL.may_load(deps.storage)?.unwrap_or_default();
+                    // Adding an existing member overwrites their weight
+                    for member in add.into_iter() {
+                        let member_addr = validate_addr(deps.api, &member.addr)?;
+                        MEMBERS.update(
+                            deps.storage,
+                            &member_addr,
+                            |old: Option<u64>| -> StdResult<_> {
+                                total = checked_sub(total, old.unwrap_or_default())?;
+                                total = checked_add(total, member.weight)?;
+                                Ok(member.weight)
+                            },
+                        )?;
+                    }
+                    // Removing an unknown member is a no-op
+                    for addr in remove.into_iter() {
+                        let remove_addr = validate_addr(deps.api, &addr)?;
+                        if let Some(weight) = MEMBERS.may_load(deps.storage, &remove_addr)? {
+                            total = checked_sub(total, weight)?;
+                            MEMBERS.remove(deps.storage, &remove_addr);
+                        }
+                    }
+                    TOTAL.save(deps.storage, &total)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "update_members")
+                        .add_attribute("total_weight", total.to_string()))
+                }
+            }
+        },
+        |deps, _, _, _: MockInstantiateMsg| -> StdResult<Response> {
+            TOTAL.save(deps.storage, &0)?;
+            Ok(Response::default())
+        },
+        |deps, _, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::Member { addr } => {
+                    let member_addr = validate_addr(deps.api, &addr)?;
+                    let weight = MEMBERS.may_load(deps.storage, &member_addr)?;
+                    Ok(to_binary(&MemberResponse { weight })?)
+                }
+                MockQueryMsg::ListMembers { start_after, limit } => {
+                    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+                    let start = start_after.map(Bound::exclusive);
+                    let members: StdResult<Vec<Member>> = MEMBERS
+                        .range(deps.storage, start, None, Order::Ascending)
+                        .take(limit)
+                        .map(|item| {
+                            let (key, weight) = item?;
+                            Ok(Member {
+                                addr: String::from_utf8(key)?,
+                                weight,
+                            })
+                        })
+                        .collect();
+                    Ok(to_binary(&MemberListResponse { members: members? })?)
+                }
+                MockQueryMsg::TotalWeight {} => {
+                    let weight = TOTAL.may_load(deps.storage)?.unwrap_or_default();
+                    Ok(to_binary(&TotalWeightResponse { weight })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
@@ -0,0 +1,389 @@
+use cosmwasm_std::{
+    to_binary, Addr, Binary, BlockInfo, CosmosMsg, Empty, Order, Response, StdResult, Storage,
+};
+use cw721::{
+    Approval, ApprovedForAllResponse, Cw721ReceiveMsg, Expiration, NumTokensResponse,
+    OwnerOfResponse, TokensResponse,
+};
+use cw_storage_plus::{Bound, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::validation::validate_addr;
+
+// Same pagination limits cw721-base uses so the enumerable queries behave like the real thing
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// Simple mocked instantiate with no params so devs can use it easily
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {}
+
+// Slimmed down cw721 ExecuteMsg, anyone can mint so tests can set up ownership directly
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    Mint {
+        token_id: String,
+        owner: String,
+    },
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    RevokeAll {
+        operator: String,
+    },
+}
+
+// Mocked Query handler with the ownership query NFT-integrating contracts rely on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Approvals {
+        token_id: String,
+    },
+    AllOperators {
+        owner: String,
+    },
+    NumTokens {},
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Version {},
+}
+
+// The response that should be returned by the Approvals query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+pub const OWNERS: Map<&str, Addr> = Map::new("owners");
+// Spenders approved for a single token
+pub const APPROVALS: Map<(&str, &Addr), Expiration> = Map::new("approvals");
+// Operators approved for every token of an owner
+pub const OPERATORS: Map<(&Addr, &Addr), Expiration> = Map::new("operators");
+
+// Check whether the sender is the owner of the token or an operator of the owner
+fn is_owner_or_operator(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    owner: &Addr,
+    sender: &Addr,
+) -> StdResult<bool> {
+    if owner == sender {
+        return Ok(true);
+    }
+    Ok(OPERATORS
+        .may_load(storage, (owner, sender))?
+        .map(|expires| !expires.is_expired(block))
+        .unwrap_or(false))
+}
+
+// Move the token to its new owner, the owner, an approved spender or an operator can do so
+fn transfer_nft(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    sender: &Addr,
+    recipient: &Addr,
+    token_id: &str,
+) -> StdResult<()> {
+    let owner = OWNERS.load(storage, token_id)?;
+    let approved = APPROVALS
+        .may_load(storage, (token_id, sender))?
+        .map(|expires| !expires.is_expired(block))
+        .unwrap_or(false);
+    if !approved && !is_owner_or_operator(storage, block, &owner, sender)? {
+        return Err(MockError::Unauthorized {}.into());
+    }
+    // Approvals belong to the previous owner so they are cleared like cw721-base does
+    let spenders: Vec<Vec<u8>> = APPROVALS
+        .prefix(token_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect();
+    for spender in spenders {
+        APPROVALS.remove(
+            storage,
+            (token_id, &Addr::unchecked(String::from_utf8(spender)?)),
+        );
+    }
+    OWNERS.save(storage, token_id, recipient)
+}
+
+// List token ids in ascending order, optionally only those held by `owner`
+fn query_tokens(
+    storage: &dyn Storage,
+    owner: Option<Addr>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    OWNERS
+        .range(storage, start, None, Order::Ascending)
+        .filter(|item| match (item, &owner) {
+            (Ok((_, token_owner)), Some(owner)) => token_owner == owner,
+            _ => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (token_id, _) = item?;
+            Ok(String::from_utf8(token_id)?)
+        })
+        .collect()
+}
+
+// List the unexpired approvals for a token
+fn query_approvals(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    token_id: &str,
+) -> StdResult<Vec<Approval>> {
+    APPROVALS
+        .prefix(token_id)
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => !expires.is_expired(block),
+            Err(_) => true,
+        })
+        .map(|item| {
+            let (spender, expires) = item?;
+            Ok(Approval {
+                spender: String::from_utf8(spender)?,
+                expires,
+            })
+        })
+        .collect()
+}
+
+pub fn contract_cw721_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, env, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            match msg {
+                MockExecuteMsg::Mint { token_id, owner } => {
+                    let owner_addr = validate_addr(deps.api, &owner)?;
+                    OWNERS.update(
+                        deps.storage,
+                        &token_id,
+                        |existing: Option<Addr>| -> StdResult<_> {
+                            match existing {
+                                Some(_) => Err(MockError::TokenClaimed {}.into()),
+                                None => Ok(owner_addr),
+                            }
+                        },
+                    )?;
+                    Ok(Response::new()
+                        .add_attribute("action", "mint")
+                        .add_attribute("minter", info.sender)
+                        .add_attribute("owner", owner)
+                        .add_attribute("token_id", token_id))
+                }
+                MockExecuteMsg::TransferNft {
+                    recipient,
+                    token_id,
+                } => {
+                    let rcpt_addr = validate_addr(deps.api, &recipient)?;
+                    transfer_nft(
+                        deps.storage,
+                        &env.block,
+                        &info.sender,
+                        &rcpt_addr,
+                        &token_id,
+                    )?;
+                    Ok(Response::new()
+                        .add_attribute("action", "transfer_nft")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("recipient", recipient)
+                        .add_attribute("token_id", token_id))
+                }
+                MockExecuteMsg::SendNft {
+                    contract,
+                    token_id,
+                    msg,
+                } => {
+                    let contract_addr = validate_addr(deps.api, &contract)?;
+                    transfer_nft(
+                        deps.storage,
+                        &env.block,
+                        &info.sender,
+                        &contract_addr,
+                        &token_id,
+                    )?;
+                    // Fire the receive hook on the receiving contract like a real cw721 does
+                    let receive_msg: CosmosMsg = Cw721ReceiveMsg {
+                        sender: info.sender.to_string(),
+                        token_id: token_id.clone(),
+                        msg,
+                    }
+                    .into_cosmos_msg(contract.clone())?;
+                    Ok(Response::new()
+                        .add_message(receive_msg)
+                        .add_attribute("action", "send_nft")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("recipient", contract)
+                        .add_attribute("token_id", token_id))
+                }
+                MockExecuteMsg::Approve {
+                    spender,
+                    token_id,
+                    expires,
+                } => {
+                    let owner = OWNERS.load(deps.storage, &token_id)?;
+                    if !is_owner_or_operator(deps.storage, &env.block, &owner, &info.sender)? {
+                        return Err(MockError::Unauthorized {}.into());
+                    }
+                    let spender_addr = validate_addr(deps.api, &spender)?;
+                    let expires = expires.unwrap_or_default();
+                    if expires.is_expired(&env.block) {
+                        return Err(MockError::ExpirationInPast {}.into());
+                    }
+                    APPROVALS.save(deps.storage, (token_id.as_str(), &spender_addr), &expires)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "approve")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("spender", spender)
+                        .add_attribute("token_id", token_id))
+                }
+                MockExecuteMsg::Revoke { spender, token_id } => {
+                    let owner = OWNERS.load(deps.storage, &token_id)?;
+                    if !is_owner_or_operator(deps.storage, &env.block, &owner, &info.sender)? {
+                        return Err(MockError::Unauthorized {}.into());
+                    }
+                    let spender_addr = validate_addr(deps.api, &spender)?;
+                    APPROVALS.remove(deps.storage, (token_id.as_str(), &spender_addr));
+                    Ok(Response::new()
+                        .add_attribute("action", "revoke")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("spender", spender)
+                        .add_attribute("token_id", token_id))
+                }
+                MockExecuteMsg::ApproveAll { operator, expires } => {
+                    let operator_addr = validate_addr(deps.api, &operator)?;
+                    let expires = expires.unwrap_or_default();
+                    if expires.is_expired(&env.block) {
+                        return Err(MockError::ExpirationInPast {}.into());
+                    }
+                    OPERATORS.save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "approve_all")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("operator", operator))
+                }
+                MockExecuteMsg::RevokeAll { operator } => {
+                    let operator_addr = validate_addr(deps.api, &operator)?;
+                    OPERATORS.remove(deps.storage, (&info.sender, &operator_addr));
+                    Ok(Response::new()
+                        .add_attribute("action", "revoke_all")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("operator", operator))
+                }
+            }
+        },
+        |_, _, _, _: MockInstantiateMsg| -> StdResult<Response> { Ok(Response::default()) },
+        |deps, env, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::OwnerOf {
+                    token_id,
+                    include_expired: _,
+                } => {
+                    let owner = OWNERS.load(deps.storage, &token_id)?;
+                    Ok(to_binary(&OwnerOfResponse {
+                        owner: owner.to_string(),
+                        approvals: query_approvals(deps.storage, &env.block, &token_id)?,
+                    })?)
+                }
+                MockQueryMsg::Approvals { token_id } => {
+                    // Make sure the token exists so a typo'd id errors instead of returning nothing
+                    OWNERS.load(deps.storage, &token_id)?;
+                    Ok(to_binary(&ApprovalsResponse {
+                        approvals: query_approvals(deps.storage, &env.block, &token_id)?,
+                    })?)
+                }
+                MockQueryMsg::AllOperators { owner } => {
+                    let owner_addr = validate_addr(deps.api, &owner)?;
+                    let operators: StdResult<Vec<Approval>> = OPERATORS
+                        .prefix(&owner_addr)
+                        .range(deps.storage, None, None, Order::Ascending)
+                        .filter(|item| match item {
+                            Ok((_, expires)) => !expires.is_expired(&env.block),
+                            Err(_) => true,
+                        })
+                        .map(|item| {
+                            let (operator, expires) = item?;
+                            Ok(Approval {
+                                spender: String::from_utf8(operator)?,
+                                expires,
+                            })
+                        })
+                        .collect();
+                    Ok(to_binary(&ApprovedForAllResponse {
+                        operators: operators?,
+                    })?)
+                }
+                MockQueryMsg::NumTokens {} => {
+                    let count = OWNERS
+                        .keys(deps.storage, None, None, Order::Ascending)
+                        .count() as u64;
+                    Ok(to_binary(&NumTokensResponse { count })?)
+                }
+                MockQueryMsg::Tokens {
+                    owner,
+                    start_after,
+                    limit,
+                } => {
+                    let owner_addr = validate_addr(deps.api, &owner)?;
+                    let tokens = query_tokens(deps.storage, Some(owner_addr), start_after, limit)?;
+                    Ok(to_binary(&TokensResponse { tokens })?)
+                }
+                MockQueryMsg::AllTokens { start_after, limit } => {
+                    let tokens = query_tokens(deps.storage, None, start_after, limit)?;
+                    Ok(to_binary(&TokensResponse { tokens })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
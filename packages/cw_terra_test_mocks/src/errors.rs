@@ -0,0 +1,201 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+// The errors the mocks fail with. The handlers still return StdResult like the contracts they stand in for,
+// so every variant turns into a StdError::GenericErr carrying the message below. Tests can match on
+// `MockError::Unauthorized {}.to_string()` instead of copying the text around.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum MockError {
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("{msg}")]
+    MockFailure { msg: String },
+
+    #[error("No such contract: the mock is unreachable")]
+    Unreachable {},
+
+    #[error("Invalid address: {addr}")]
+    InvalidAddress { addr: String },
+
+    #[error("Account {account} is frozen")]
+    AccountFrozen { account: String },
+
+    #[error("Cannot move {amount}, only {available} of the balance is not locked")]
+    BalanceLocked { amount: String, available: String },
+
+    #[error("Token transfers are paused")]
+    TokenPaused {},
+
+    #[error("Insufficient balance: {balance} held, {amount} needed")]
+    InsufficientBalance { balance: String, amount: String },
+
+    #[error("Cannot send tokens to the token contract itself")]
+    SelfSend {},
+
+    #[error("{kind} queries are not supported here")]
+    UnsupportedQuery { kind: String },
+
+    #[error("Asset infos do not match the pair")]
+    PairMismatch {},
+
+    #[error("Asset mismatch")]
+    AssetMismatch {},
+
+    #[error("Asset mismatch: {offer} is not one of the pair's assets ({asset0}, {asset1})")]
+    OfferAssetMismatch {
+        offer: String,
+        asset0: String,
+        asset1: String,
+    },
+
+    #[error("Unknown receive hook {received}, expected one of: {expected}")]
+    UnknownReceiveHook { received: String, expected: String },
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Commission rate must be at most 1")]
+    InvalidCommissionRate {},
+
+    #[error("Invalid mock config: {reason}")]
+    InvalidMockConfig { reason: String },
+
+    #[error("Rebase factor must be above zero")]
+    InvalidRebaseFactor {},
+
+    #[error("Swap decreased the constant product from {before} to {after}")]
+    KDecreased { before: String, after: String },
+
+    #[error("Exchange rate must be above zero")]
+    ZeroExchangeRate {},
+
+    #[error("Pool has no shares")]
+    PoolHasNoShares {},
+
+    #[error("Pool is empty")]
+    PoolEmpty {},
+
+    #[error("Return amount is zero")]
+    ZeroReturnAmount {},
+
+    #[error("Insufficient liquidity: the swap would drain the pool")]
+    InsufficientLiquidity {},
+
+    #[error("Deposit too small to mint any shares")]
+    DepositTooSmall {},
+
+    #[error("Deposit of {deposit} is below the minimum of {minimum}")]
+    DepositBelowMinimum { deposit: String, minimum: String },
+
+    #[error("No offer funds sent")]
+    NoOfferFunds {},
+
+    #[error("Swap offers {offer} but {funds} was sent")]
+    OfferFundsMismatch { offer: String, funds: String },
+
+    #[error("The first operation offers {expected} but {received} was sent")]
+    OfferDenomMismatch { expected: String, received: String },
+
+    #[error(
+        "Assertion failed; minimum receive amount: {minimum_receive}, swap amount: {return_amount}"
+    )]
+    MinimumReceive {
+        minimum_receive: String,
+        return_amount: String,
+    },
+
+    #[error("Must provide operations")]
+    NoOperations {},
+
+    #[error("NativeSwap operations are not supported by the mock")]
+    NativeSwapUnsupported {},
+
+    #[error("Operations are not a connected path")]
+    DisconnectedOperations {},
+
+    #[error("No registered pair for {offer} -> {ask}")]
+    NoPairForOperation { offer: String, ask: String },
+
+    #[error("No price set for {base}/{quote}")]
+    NoPrice { base: String, quote: String },
+
+    #[error("Caller is not the owner")]
+    NotOwner {},
+
+    #[error("Caller is not the pending owner")]
+    NotPendingOwner {},
+
+    #[error("Expiry time has already passed")]
+    ExpiryPassed {},
+
+    #[error("The ownership transfer has expired")]
+    TransferExpired {},
+
+    #[error("Proposal is not open")]
+    ProposalNotOpen {},
+
+    #[error("Proposal voting period has expired")]
+    VotingPeriodExpired {},
+
+    #[error("Already voted")]
+    AlreadyVoted {},
+
+    #[error("Proposal has expired and can no longer be executed")]
+    ProposalExpired {},
+
+    #[error("Proposal must have passed and not yet been executed")]
+    ProposalNotPassed {},
+
+    #[error("Token already claimed")]
+    TokenClaimed {},
+
+    #[error("Approval expiration is in the past")]
+    ExpirationInPast {},
+}
+
+impl From<MockError> for StdError {
+    fn from(err: MockError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_to_a_generic_error_with_the_message() {
+        let err: StdError = MockError::InsufficientBalance {
+            balance: "5".to_string(),
+            amount: "10".to_string(),
+        }
+        .into();
+        assert_eq!(
+            err,
+            StdError::generic_err("Insufficient balance: 5 held, 10 needed")
+        );
+    }
+
+    #[test]
+    fn messages_stay_stable() {
+        // Consumers still matching on the text rely on these
+        assert_eq!(MockError::Unauthorized {}.to_string(), "Unauthorized");
+        assert_eq!(MockError::AssetMismatch {}.to_string(), "Asset mismatch");
+        assert_eq!(
+            MockError::TokenPaused {}.to_string(),
+            "Token transfers are paused"
+        );
+        assert_eq!(
+            MockError::NoOfferFunds {}.to_string(),
+            "No offer funds sent"
+        );
+        assert_eq!(
+            MockError::MockFailure {
+                msg: "Mock query failure".to_string()
+            }
+            .to_string(),
+            "Mock query failure"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use cosmwasm_std::{to_binary, Binary, Empty, Response, StdResult};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+use terraswap::asset::AssetInfo;
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::terraswap_mock::{asset_infos_match, PairResponse};
+use crate::validation::validate_addr;
+
+// Same page sizes as the Terraswap factory
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// Mocked instantiate, the pairs the factory starts out knowing about
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    pub pairs: Vec<PairResponse>,
+}
+
+// Slimmed down Terraswap factory ExecuteMsg, pairs are registered as they are rather than instantiated by the factory
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    RegisterPair { pair: PairResponse },
+}
+
+// Mocked Query handler with the Terraswap factory Pair and Pairs queries
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    Pair {
+        asset_infos: [AssetInfo; 2],
+    },
+    // asset_info is an addition to the Terraswap query, when given only the pairs trading that asset are listed
+    Pairs {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+        #[serde(default)]
+        asset_info: Option<AssetInfo>,
+    },
+    Version {},
+}
+
+// The response that should be returned by the Pairs query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairsResponse {
+    pub pairs: Vec<PairResponse>,
+}
+
+// Kept sorted by pair_key so Pairs pages through them in the same order every time
+pub const PAIRS: Item<Vec<PairResponse>> = Item::new("pairs");
+
+// The order independent key of a pair, the two asset names sorted and joined like the factory's pair key
+pub fn pair_key(asset_infos: &[AssetInfo; 2]) -> String {
+    let mut names = [asset_infos[0].to_string(), asset_infos[1].to_string()];
+    names.sort();
+    names.join(":")
+}
+
+fn register_pair(pairs: &mut Vec<PairResponse>, pair: PairResponse) {
+    let key = pair_key(&pair.asset_infos);
+    match pairs.binary_search_by(|existing| pair_key(&existing.asset_infos).cmp(&key)) {
+        Ok(index) => pairs[index] = pair,
+        Err(index) => pairs.insert(index, pair),
+    }
+}
+
+pub fn contract_factory_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, _, _, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            match msg {
+                MockExecuteMsg::RegisterPair { pair } => {
+                    validate_addr(deps.api, &pair.contract_addr)?;
+                    let mut pairs = PAIRS.load(deps.storage)?;
+                    let contract_addr = pair.contract_addr.clone();
+                    register_pair(&mut pairs, pair);
+                    PAIRS.save(deps.storage, &pairs)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "register_pair")
+                        .add_attribute("pair_contract_addr", contract_addr))
+                }
+            }
+        },
+        |deps, _, _, msg: MockInstantiateMsg| -> StdResult<Response> {
+            let mut pairs = vec![];
+            for pair in msg.pairs {
+                validate_addr(deps.api, &pair.contract_addr)?;
+                register_pair(&mut pairs, pair);
+            }
+            PAIRS.save(deps.storage, &pairs)?;
+            Ok(Response::default())
+        },
+        |deps, _, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::Pair { asset_infos } => {
+                    let pair = PAIRS
+                        .load(deps.storage)?
+                        .into_iter()
+                        .find(|pair| asset_infos_match(&pair.asset_infos, &asset_infos))
+                        .ok_or(MockError::NoPairForOperation {
+                            offer: asset_infos[0].to_string(),
+                            ask: asset_infos[1].to_string(),
+                        })?;
+                    Ok(to_binary(&pair)?)
+                }
+                MockQueryMsg::Pairs {
+                    start_after,
+                    limit,
+                    asset_info,
+                } => {
+                    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+                    let start_key = start_after.as_ref().map(pair_key);
+                    // The filter applies before the limit so every page is full until the matches run out
+                    let pairs = PAIRS
+                        .load(deps.storage)?
+                        .into_iter()
+                        .filter(|pair| match &start_key {
+                            Some(start_key) => pair_key(&pair.asset_infos) > *start_key,
+                            None => true,
+                        })
+                        .filter(|pair| match &asset_info {
+                            Some(asset_info) => pair.asset_infos.contains(asset_info),
+                            None => true,
+                        })
+                        .take(limit)
+                        .collect();
+                    Ok(to_binary(&PairsResponse { pairs })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
@@ -0,0 +1,205 @@
+use cosmwasm_std::{Binary, StdError, StdResult};
+use lazy_static::lazy_static;
+
+use crate::errors::MockError;
+use crate::lock::Lock;
+
+// These lazy statics allow you the dev to make the mocks fail on purpose so error handling around them can be tested.
+// They are shared by every mock in the crate, so the next call to any mock is the one that fails.
+lazy_static! {
+    // How many of the upcoming queries should fail, counts down by one each time a query fails
+    static ref FAIL_NEXT_QUERY: Lock<u32> = Lock::new(0);
+    // How many of the upcoming executes should fail, counts down by one each time an execute fails
+    static ref FAIL_NEXT_EXECUTE: Lock<u32> = Lock::new(0);
+    // A specific error for the next query or execute to return instead of the generic mock failure
+    static ref INJECTED_ERROR: Lock<Option<StdError>> = Lock::new(None);
+    // Every n-th call to a mock fails, as (n, calls counted so far). An n of 0 turns it off
+    static ref FAIL_EVERY: Lock<(u32, u32)> = Lock::new((0, 0));
+    // Whether queries answer with bytes that don't deserialize into anything
+    static ref RETURN_MALFORMED: Lock<bool> = Lock::new(false);
+    // Overrides the text of the generic errors the failure toggles return
+    static ref ERROR_MESSAGE: Lock<Option<String>> = Lock::new(None);
+    // Whether the mocks behave like a contract that can't be reached at all
+    static ref CONTRACT_UNREACHABLE: Lock<bool> = Lock::new(false);
+}
+
+// Make every query and execute on any mock fail the way a call to a missing or halted contract does,
+// until turned off again. Unlike the other toggles this one never counts down.
+pub fn set_contract_unreachable(unreachable: bool) -> bool {
+    let mut contract_unreachable = CONTRACT_UNREACHABLE.write();
+    *contract_unreachable = unreachable;
+    return *contract_unreachable;
+}
+
+pub fn get_contract_unreachable() -> bool {
+    return *CONTRACT_UNREACHABLE.read();
+}
+
+fn check_unreachable() -> StdResult<()> {
+    if get_contract_unreachable() {
+        return Err(MockError::Unreachable {}.into());
+    }
+    Ok(())
+}
+
+// Set the message of the generic error returned when a failure toggle fires, so tests can assert on the text
+// their contract forwards. Without it the errors read "Mock query failure" or "Mock execute failure".
+pub fn set_error_message(message: String) -> String {
+    let mut error_message = ERROR_MESSAGE.write();
+    *error_message = Some(message);
+    return error_message.clone().unwrap_or_default();
+}
+
+// Go back to the default error messages
+pub fn clear_error_message() {
+    let mut error_message = ERROR_MESSAGE.write();
+    *error_message = None;
+}
+
+// The generic error a failure toggle returns, using the configured message if one was set
+pub fn mock_failure(default_message: &str) -> StdError {
+    let error_message = ERROR_MESSAGE.read();
+    MockError::MockFailure {
+        msg: error_message
+            .as_deref()
+            .unwrap_or(default_message)
+            .to_string(),
+    }
+    .into()
+}
+
+// Make every query on any mock succeed with garbage bytes until turned off again,
+// so from_binary error handling in the contract under test can be exercised
+pub fn set_return_malformed(malformed: bool) -> bool {
+    let mut return_malformed = RETURN_MALFORMED.write();
+    *return_malformed = malformed;
+    return *return_malformed;
+}
+
+pub fn get_return_malformed() -> bool {
+    return *RETURN_MALFORMED.read();
+}
+
+// Called by every mock query handler after check_query_failure, the garbage to answer with if requested
+pub fn malformed_response() -> Option<Binary> {
+    if get_return_malformed() {
+        return Some(Binary::from(b"\x00not json".to_vec()));
+    }
+    None
+}
+
+// Make every n-th query or execute on any mock fail and the others succeed, like a flaky dependency would.
+// Setting it restarts the call count, so with n = 3 the calls go ok, ok, fail, ok, ok, fail...
+pub fn set_fail_every(n: u32) -> u32 {
+    let mut fail_every = FAIL_EVERY.write();
+    *fail_every = (n, 0);
+    return fail_every.0;
+}
+
+pub fn get_fail_every() -> u32 {
+    return FAIL_EVERY.read().0;
+}
+
+// Count the call and error if it is an n-th one
+fn count_intermittent_failure(error: &str) -> StdResult<()> {
+    let mut fail_every = FAIL_EVERY.write();
+    let (n, calls) = *fail_every;
+    if n == 0 {
+        return Ok(());
+    }
+    fail_every.1 = calls + 1;
+    if fail_every.1 % n == 0 {
+        return Err(mock_failure(error));
+    }
+    Ok(())
+}
+
+// Make the next query or execute on any mock return exactly this error, e.g. StdError::overflow or StdError::not_found,
+// so the error mapping of the contract under test can be checked. Passing None clears a pending error.
+pub fn set_injected_error(error: Option<StdError>) -> bool {
+    let mut injected = INJECTED_ERROR.write();
+    *injected = error;
+    return injected.is_some();
+}
+
+pub fn has_injected_error() -> bool {
+    return INJECTED_ERROR.read().is_some();
+}
+
+// Hand out the injected error, if any, clearing it so it only fires once
+fn take_injected_error() -> StdResult<()> {
+    match INJECTED_ERROR.write().take() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+// Make the next query on any mock return an error, the toggle clears itself once it fired
+pub fn set_fail_next_query(fail: bool) -> u32 {
+    return set_fail_next_queries(if fail { 1 } else { 0 });
+}
+
+// Make the next `count` queries on any mock return an error
+pub fn set_fail_next_queries(count: u32) -> u32 {
+    let mut remaining = FAIL_NEXT_QUERY.write();
+    *remaining = count;
+    return *remaining;
+}
+
+pub fn get_fail_next_queries() -> u32 {
+    return *FAIL_NEXT_QUERY.read();
+}
+
+// Called at the start of every mock query handler, errors if a failure was requested
+pub fn check_query_failure() -> StdResult<()> {
+    check_unreachable()?;
+    take_injected_error()?;
+    count_intermittent_failure("Mock query failure")?;
+    let mut remaining = FAIL_NEXT_QUERY.write();
+    if *remaining > 0 {
+        *remaining -= 1;
+        return Err(mock_failure("Mock query failure"));
+    }
+    Ok(())
+}
+
+// Make the next execute on any mock return an error, the toggle clears itself once it fired.
+// The failing execute reverts like any other, so state changes made by the calling contract roll back too.
+pub fn set_fail_next_execute(fail: bool) -> u32 {
+    return set_fail_next_executes(if fail { 1 } else { 0 });
+}
+
+// Make the next `count` executes on any mock return an error
+pub fn set_fail_next_executes(count: u32) -> u32 {
+    let mut remaining = FAIL_NEXT_EXECUTE.write();
+    *remaining = count;
+    return *remaining;
+}
+
+pub fn get_fail_next_executes() -> u32 {
+    return *FAIL_NEXT_EXECUTE.read();
+}
+
+// Put the statics back to the defaults above, see reset_mock_state
+pub(crate) fn reset_state() {
+    set_fail_next_queries(0);
+    set_fail_next_executes(0);
+    set_injected_error(None);
+    set_fail_every(0);
+    set_return_malformed(false);
+    clear_error_message();
+    set_contract_unreachable(false);
+}
+
+// Called at the start of every mock execute handler, errors if a failure was requested
+pub fn check_execute_failure() -> StdResult<()> {
+    check_unreachable()?;
+    take_injected_error()?;
+    count_intermittent_failure("Mock execute failure")?;
+    let mut remaining = FAIL_NEXT_EXECUTE.write();
+    if *remaining > 0 {
+        *remaining -= 1;
+        return Err(mock_failure("Mock execute failure"));
+    }
+    Ok(())
+}
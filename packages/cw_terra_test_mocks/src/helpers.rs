@@ -0,0 +1,232 @@
+// Setup helpers for consumer tests that drive a multi-test App, these panic like the assertion helpers do
+#[cfg(feature = "terraswap")]
+use crate::terraswap_mock::{
+    contract_terraswap_mock, ConfigResponse, MockConfig, MockExecuteMsg, MockInstantiateMsg,
+    MockQueryMsg, MockSudoMsg, PoolResponse, MAX_LIMIT,
+};
+#[cfg(feature = "terraswap")]
+use cosmwasm_std::{from_slice, Decimal, StdError, Uint128};
+use cosmwasm_std::{Addr, Coin, StdResult};
+#[cfg(feature = "terraswap")]
+use cw20::{AllAccountsResponse, BalanceResponse, TokenInfoResponse};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use terra_multi_test::App;
+#[cfg(feature = "terraswap")]
+use terra_multi_test::Executor;
+#[cfg(feature = "terraswap")]
+use terraswap::asset::Asset;
+
+// Typed smart queries against any mock in the App without the query_wasm_smart boilerplate,
+// e.g. `let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {})?;`
+pub trait QueryExt {
+    fn query_mock<T: DeserializeOwned, M: Serialize>(&self, addr: &Addr, msg: &M) -> StdResult<T>;
+}
+
+impl QueryExt for App {
+    fn query_mock<T: DeserializeOwned, M: Serialize>(&self, addr: &Addr, msg: &M) -> StdResult<T> {
+        self.wrap().query_wasm_smart(addr.to_string(), msg)
+    }
+}
+
+// Enough for the commission top-ups, each swap gets the pool most of the way to the target
+#[cfg(feature = "terraswap")]
+const MAX_DRAIN_SWAPS: u32 = 32;
+
+#[cfg(feature = "terraswap")]
+fn query_pool(app: &App, pair: &Addr) -> PoolResponse {
+    app.query_mock(pair, &MockQueryMsg::Pool {})
+        .expect("Pool query failed while draining the pool")
+}
+
+// Swap the first asset of the pair into the pool until the second reserve is down to `fraction` of where it started
+// and return the final pool. The fraction has to leave something in the pool, an empty reserve breaks the constant product.
+#[cfg(feature = "terraswap")]
+pub fn drain_pool(app: &mut App, pair: &Addr, fraction: Decimal) -> PoolResponse {
+    assert!(
+        !fraction.is_zero() && fraction < Decimal::one(),
+        "drain_pool fraction must be above 0 and below 1"
+    );
+    let mut pool = query_pool(app, pair);
+    let target = pool.assets[1].amount * fraction;
+    assert!(
+        !target.is_zero(),
+        "drain_pool fraction would leave the pool with an empty reserve"
+    );
+    for _ in 0..MAX_DRAIN_SWAPS {
+        if pool.assets[1].amount <= target {
+            break;
+        }
+        // The offer that takes the reserve to the target ignoring the commission, at most the whole offer reserve per swap
+        let needed = pool.assets[0]
+            .amount
+            .multiply_ratio(pool.assets[1].amount, target)
+            .saturating_sub(pool.assets[0].amount);
+        let offer_amount = needed.min(pool.assets[0].amount).max(Uint128::new(1));
+        app.execute_contract(
+            Addr::unchecked("drainer"),
+            pair.clone(),
+            &MockExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: pool.assets[0].info.clone(),
+                    amount: offer_amount,
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[],
+        )
+        .expect("Swap failed while draining the pool");
+        pool = query_pool(app, pair);
+    }
+    pool
+}
+
+// The balance of every account the terraswap mock token tracks, paged through with AllAccounts in account order
+#[cfg(feature = "terraswap")]
+pub(crate) fn tracked_balances(app: &App, token: &Addr) -> Vec<(String, Uint128)> {
+    let mut balances = vec![];
+    let mut start_after: Option<String> = None;
+    loop {
+        let page: AllAccountsResponse = app
+            .query_mock(
+                token,
+                &MockQueryMsg::AllAccounts {
+                    start_after: start_after.clone(),
+                    limit: Some(MAX_LIMIT),
+                },
+            )
+            .expect("AllAccounts query failed");
+        for account in page.accounts.iter() {
+            let balance: BalanceResponse = app
+                .query_mock(
+                    token,
+                    &MockQueryMsg::Balance {
+                        address: account.clone(),
+                    },
+                )
+                .expect("Balance query failed");
+            balances.push((account.clone(), balance.balance));
+        }
+        if page.accounts.len() < MAX_LIMIT as usize {
+            return balances;
+        }
+        start_after = page.accounts.last().cloned();
+    }
+}
+
+// Capture the reserves, commission, token info and balances of the terraswap mock at `addr`, for printing in a failing test
+#[cfg(feature = "terraswap")]
+pub fn dump_mock_config(app: &App, addr: &Addr) -> MockConfig {
+    let token_info: TokenInfoResponse = app
+        .query_mock(addr, &MockQueryMsg::TokenInfo {})
+        .expect("TokenInfo query failed");
+    let config: ConfigResponse = app
+        .query_mock(addr, &MockQueryMsg::Config {})
+        .expect("Config query failed");
+    MockConfig {
+        pool: app
+            .query_mock(addr, &MockQueryMsg::Pool {})
+            .expect("Pool query failed"),
+        commission_rate: config.commission_rate,
+        commission_split: config.commission_split,
+        token_info,
+        balances: tracked_balances(app, addr),
+    }
+}
+
+// Replay a MockConfig fixture onto the pair at `addr`, e.g. one written out from dump_mock_config against a snapshot of
+// mainnet state. The pool (whatever its assets), commission, token info and balances of that pair are replaced through
+// the LoadConfig sudo, other pairs and the global setters are left alone. The fixture is parsed and checked in full
+// before anything is written, so a bad one leaves the pair as it was.
+#[cfg(feature = "terraswap")]
+pub fn load_mock_config_from_json(app: &mut App, addr: &Addr, json: &str) -> StdResult<()> {
+    let config: MockConfig = from_slice(json.as_bytes())?;
+    app.wasm_sudo(addr.clone(), &MockSudoMsg::LoadConfig { config })
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(())
+}
+
+// Seconds a block takes when advancing by blocks, the same as multi-test's next_block
+const BLOCK_TIME_SECS: u64 = 5;
+
+// Move the App forward `blocks` blocks, the block time moves along by BLOCK_TIME_SECS per block
+pub fn advance_blocks(app: &mut App, blocks: u64) {
+    app.update_block(|block| {
+        block.height += blocks;
+        block.time = block.time.plus_seconds(blocks * BLOCK_TIME_SECS);
+    });
+}
+
+// Move the App's block time forward `secs` seconds without producing blocks
+pub fn advance_time(app: &mut App, secs: u64) {
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(secs);
+    });
+}
+
+// The address the App gives the contract instantiated `instance`-th, counting from 0. multi-test hands addresses out in
+// instantiation order instead of deriving them from a salt, so this is as close to an instantiate2 address as it gets:
+// the contract under test can be given the address before the mock exists.
+pub fn predict_contract_address(instance: u64) -> Addr {
+    Addr::unchecked(format!("Contract #{}", instance))
+}
+
+// Instantiate a terraswap mock that has to land on predict_contract_address(instance), the instantiate2 stand-in.
+// Panics if the App gave it another address, i.e. a different number of contracts was instantiated before it.
+#[cfg(feature = "terraswap")]
+pub fn instantiate2_terraswap_mock(
+    app: &mut App,
+    sender: &Addr,
+    admin: Option<String>,
+    instance: u64,
+) -> Addr {
+    let predicted = predict_contract_address(instance);
+    let code_id = app.store_code(contract_terraswap_mock());
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            sender.clone(),
+            &MockInstantiateMsg::default(),
+            &[],
+            "terraswap mock",
+            admin,
+        )
+        .expect("Instantiating the terraswap mock failed");
+    assert_eq!(
+        addr, predicted,
+        "the terraswap mock was expected at {} but got {}",
+        predicted, addr
+    );
+    addr
+}
+
+// Collects the setup a test App needs before the mocks are used, so native legs of swaps have funds to move.
+// MockAppBuilder::new().with_funded_account("alice", coins(1_000, "uusd")).build(app)
+#[derive(Default)]
+pub struct MockAppBuilder {
+    funded_accounts: Vec<(Addr, Vec<Coin>)>,
+}
+
+impl MockAppBuilder {
+    pub fn new() -> Self {
+        MockAppBuilder::default()
+    }
+
+    // Give `addr` these native coins when the App is built, any denom works as the bank has no denom registry
+    pub fn with_funded_account(mut self, addr: impl Into<String>, coins: Vec<Coin>) -> Self {
+        self.funded_accounts
+            .push((Addr::unchecked(addr.into()), coins));
+        self
+    }
+
+    // Set the bank balances of every funded account on the App and hand it back
+    pub fn build(self, mut app: App) -> App {
+        for (addr, coins) in self.funded_accounts {
+            app.init_bank_balance(&addr, coins)
+                .expect("Funding an account failed while building the App");
+        }
+        app
+    }
+}
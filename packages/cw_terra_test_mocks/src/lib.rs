@@ -1,2 +1,49 @@
+// Each mock sits behind a cargo feature of the same name so consumers only compile what they use
+#[cfg(feature = "anchor")]
 pub mod anchor_mock;
+pub mod assertions;
+pub mod assets;
+pub mod capabilities;
+#[cfg(feature = "terraswap")]
+pub mod client;
+#[cfg(feature = "cw1")]
+pub mod cw1_whitelist_mock;
+#[cfg(feature = "cw3")]
+pub mod cw3_mock;
+#[cfg(feature = "cw4")]
+pub mod cw4_group_mock;
+#[cfg(feature = "cw721")]
+pub mod cw721_mock;
+pub mod errors;
+#[cfg(feature = "factory")]
+pub mod factory_mock;
+pub mod failures;
+pub mod helpers;
+mod lock;
+mod macros;
+#[cfg(feature = "lsd")]
+pub mod lsd_mock;
+pub mod migrate;
+#[cfg(feature = "oracle")]
+pub mod oracle_mock;
+#[cfg(feature = "ownable")]
+pub mod ownable_mock;
+pub mod prelude;
+#[cfg(feature = "terraswap")]
+pub mod querier;
+pub mod reset;
+#[cfg(feature = "router")]
+pub mod router_mock;
+#[cfg(feature = "staking")]
+pub mod staking_mock;
+#[cfg(feature = "terraswap")]
 pub mod terraswap_mock;
+pub mod trace;
+pub mod validation;
+
+// Paths the exported macros expand to, so consumers don't need the same dependency names
+#[doc(hidden)]
+pub mod __private {
+    pub use cosmwasm_std;
+    pub use terra_multi_test;
+}
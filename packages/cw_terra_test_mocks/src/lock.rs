@@ -0,0 +1,52 @@
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+// The RwLock the mock statics live in. A test that panics while holding the lock poisons a plain RwLock and every
+// later `.read().unwrap()` panics too, failing the rest of the suite. The statics are plain values that are always
+// left in a usable state, so the accessors here take the value out of a poisoned lock instead.
+pub(crate) struct Lock<T> {
+    inner: RwLock<T>,
+}
+
+impl<T> Lock<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Lock {
+            inner: RwLock::new(value),
+        }
+    }
+
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn a_panic_while_writing_does_not_poison_later_reads() {
+        let lock = Arc::new(Lock::new(1u32));
+        let writer = Arc::clone(&lock);
+        let result = thread::spawn(move || {
+            let mut value = writer.write();
+            *value = 2;
+            panic!("the test holding the lock failed");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(lock.inner.is_poisoned());
+
+        // Another thread reads the value the panicking one left and can still write
+        let reader = Arc::clone(&lock);
+        let read = thread::spawn(move || *reader.read()).join().unwrap();
+        assert_eq!(read, 2);
+        *lock.write() = 3;
+        assert_eq!(*lock.read(), 3);
+    }
+}
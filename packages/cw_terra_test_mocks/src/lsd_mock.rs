@@ -0,0 +1,279 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Empty, Response,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Item, Map};
+use lazy_static::lazy_static;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::lock::Lock;
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::validation::validate_addr;
+
+// These lazy statics allow you the dev to set the derivative exchange rate and unbonding period before you use the liquid staking mock
+lazy_static! {
+    // uluna one derivative token is worth, bonding mints at this rate and unbonding pays out at it
+    static ref LSD_EXCHANGE_RATE: Lock<Decimal256> = Lock::new(Decimal256::one());
+    // Blocks between an Unbond and the uluna being withdrawable
+    static ref UNBONDING_PERIOD: Lock<u64> = Lock::new(100);
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_lsd_exchange_rate(new_rate: Decimal256) -> Decimal256 {
+    let mut rate = LSD_EXCHANGE_RATE.write();
+    *rate = new_rate;
+    return *rate;
+}
+
+pub fn get_lsd_exchange_rate() -> Decimal256 {
+    return *LSD_EXCHANGE_RATE.read();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_unbonding_period(new_period: u64) -> u64 {
+    let mut period = UNBONDING_PERIOD.write();
+    *period = new_period;
+    return *period;
+}
+
+pub fn get_unbonding_period() -> u64 {
+    return *UNBONDING_PERIOD.read();
+}
+
+// Put the statics back to the defaults above, see reset_mock_state
+pub(crate) fn reset_state() {
+    set_lsd_exchange_rate(Decimal256::one());
+    set_unbonding_period(100);
+}
+
+// Mocked instantiate, the cw20 derivative token the mock mints and burns
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    pub token: String,
+}
+
+// Slimmed down Prism/Stader style hub ExecuteMsg, unbonding happens through a cw20 Send of the derivative
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Bond the sent uluna and mint the derivative for it
+    Bond {},
+    /// Pay out the uluna of unbonds whose unbonding period is over
+    WithdrawUnbonded {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Burn the sent derivative and start unbonding the uluna it is worth
+    Unbond {},
+}
+
+// Mocked Query handler, only State is needed to read the exchange rate
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    State {},
+    UnbondRequests { address: String },
+    Version {},
+}
+
+// The response that should be returned by the State query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateResponse {
+    pub exchange_rate: Decimal256,
+    pub total_bond_amount: Uint128,
+    pub unbonding_period: u64,
+}
+
+// The response that should be returned by the UnbondRequests query, oldest request first
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondRequestsResponse {
+    pub address: String,
+    pub requests: Vec<UnbondRequest>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub token: Addr,
+}
+
+// One Unbond waiting in the queue, its uluna can be withdrawn from release_height on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondRequest {
+    pub amount: Uint128,
+    pub release_height: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const TOTAL_BONDED: Item<Uint128> = Item::new("total_bonded");
+pub const UNBOND_REQUESTS: Map<&Addr, Vec<UnbondRequest>> = Map::new("unbond_requests");
+
+pub fn contract_lsd_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, env, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            let config = CONFIG.load(deps.storage)?;
+            match msg {
+                MockExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender,
+                    amount,
+                    msg,
+                }) => match from_binary(&msg)? {
+                    Cw20HookMsg::Unbond {} => {
+                        // Only the derivative can be unbonded
+                        if info.sender != config.token {
+                            return Err(MockError::Unauthorized {}.into());
+                        }
+                        let staker = validate_addr(deps.api, &sender)?;
+                        let unbond_amount: Uint128 =
+                            (Uint256::from(amount) * get_lsd_exchange_rate()).into();
+                        TOTAL_BONDED.update(deps.storage, |total| -> StdResult<_> {
+                            Ok(total.saturating_sub(unbond_amount))
+                        })?;
+                        let release_height = env.block.height + get_unbonding_period();
+                        // Every unbond is its own batch maturing on its own
+                        UNBOND_REQUESTS.update(
+                            deps.storage,
+                            &staker,
+                            |requests: Option<Vec<UnbondRequest>>| -> StdResult<_> {
+                                let mut requests = requests.unwrap_or_default();
+                                requests.push(UnbondRequest {
+                                    amount: unbond_amount,
+                                    release_height,
+                                });
+                                Ok(requests)
+                            },
+                        )?;
+                        Ok(Response::new()
+                            .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                                contract_addr: config.token.to_string(),
+                                funds: vec![],
+                                msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+                            }))
+                            .add_attributes(vec![
+                                attr("action", "unbond"),
+                                attr("staker", staker),
+                                attr("burn_amount", amount),
+                                attr("unbond_amount", unbond_amount),
+                                attr("release_height", release_height.to_string()),
+                            ]))
+                    }
+                },
+                MockExecuteMsg::Bond {} => {
+                    // Check uluna deposit
+                    let bond_amount = info
+                        .funds
+                        .iter()
+                        .find(|c| c.denom == "uluna")
+                        .map(|c| c.amount)
+                        .unwrap_or_default();
+                    if bond_amount.is_zero() {
+                        return Err(MockError::NoOfferFunds {}.into());
+                    }
+                    // A derivative worth nothing would mint without bound
+                    let exchange_rate = get_lsd_exchange_rate();
+                    if exchange_rate.is_zero() {
+                        return Err(MockError::ZeroExchangeRate {}.into());
+                    }
+                    let mint_amount: Uint128 = (Uint256::from(bond_amount) / exchange_rate).into();
+                    TOTAL_BONDED.update(deps.storage, |total| -> StdResult<_> {
+                        Ok(total.checked_add(bond_amount)?)
+                    })?;
+                    Ok(Response::new()
+                        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                            contract_addr: config.token.to_string(),
+                            funds: vec![],
+                            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                                recipient: info.sender.to_string(),
+                                amount: mint_amount,
+                            })?,
+                        }))
+                        .add_attributes(vec![
+                            attr("action", "bond"),
+                            attr("staker", info.sender),
+                            attr("bond_amount", bond_amount),
+                            attr("mint_amount", mint_amount),
+                        ]))
+                }
+                MockExecuteMsg::WithdrawUnbonded {} => {
+                    // Pay out the matured batches and keep the rest queued
+                    let (matured, pending): (Vec<UnbondRequest>, Vec<UnbondRequest>) =
+                        UNBOND_REQUESTS
+                            .may_load(deps.storage, &info.sender)?
+                            .unwrap_or_default()
+                            .into_iter()
+                            .partition(|request| request.release_height <= env.block.height);
+                    let mut amount = Uint128::zero();
+                    for request in matured.iter() {
+                        amount = amount.checked_add(request.amount)?;
+                    }
+                    if pending.is_empty() {
+                        UNBOND_REQUESTS.remove(deps.storage, &info.sender);
+                    } else {
+                        UNBOND_REQUESTS.save(deps.storage, &info.sender, &pending)?;
+                    }
+                    let mut response = Response::new();
+                    // Bank sends refuse zero amounts so only pay out when something matured
+                    if !amount.is_zero() {
+                        response = response.add_message(BankMsg::Send {
+                            to_address: info.sender.to_string(),
+                            amount: vec![Coin {
+                                denom: "uluna".to_string(),
+                                amount,
+                            }],
+                        });
+                    }
+                    Ok(response.add_attributes(vec![
+                        attr("action", "withdraw_unbonded"),
+                        attr("staker", info.sender),
+                        attr("amount", amount),
+                    ]))
+                }
+            }
+        },
+        |deps, _, _, msg: MockInstantiateMsg| -> StdResult<Response> {
+            CONFIG.save(
+                deps.storage,
+                &Config {
+                    token: validate_addr(deps.api, &msg.token)?,
+                },
+            )?;
+            TOTAL_BONDED.save(deps.storage, &Uint128::zero())?;
+            Ok(Response::default())
+        },
+        |deps, _, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::State {} => Ok(to_binary(&StateResponse {
+                    exchange_rate: get_lsd_exchange_rate(),
+                    total_bond_amount: TOTAL_BONDED.load(deps.storage)?,
+                    unbonding_period: get_unbonding_period(),
+                })?),
+                MockQueryMsg::UnbondRequests { address } => {
+                    let addr = validate_addr(deps.api, &address)?;
+                    let requests = UNBOND_REQUESTS
+                        .may_load(deps.storage, &addr)?
+                        .unwrap_or_default();
+                    Ok(to_binary(&UnbondRequestsResponse { address, requests })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
@@ -0,0 +1,32 @@
+// Build a query-only mock from a query enum and the canned response of each variant, for one-off dependency stubs
+// that don't deserve a whole mock module. Instantiate and execute take `{}` and do nothing, the failure toggles apply
+// to queries like they do for every other mock. The responses can't borrow from the test, use owned values.
+//
+// let contract = canned_query_mock!(StubQueryMsg, {
+//     StubQueryMsg::Price {} => PriceResponse { rate: Decimal::one() },
+//     StubQueryMsg::Owner {} => OwnerResponse { owner: "owner".to_string() },
+// });
+#[macro_export]
+macro_rules! canned_query_mock {
+    ($query:ty, { $($pattern:pat => $response:expr),+ $(,)? }) => {{
+        use $crate::__private::cosmwasm_std::{to_binary, Binary, Empty, Response, StdResult};
+        use $crate::__private::terra_multi_test::{Contract, ContractWrapper};
+        let contract = ContractWrapper::new(
+            |_, _, _, _: Empty| -> StdResult<Response> {
+                $crate::failures::check_execute_failure()?;
+                Ok(Response::default())
+            },
+            |_, _, _, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+            |_, _, msg: $query| -> StdResult<Binary> {
+                $crate::failures::check_query_failure()?;
+                if let Some(malformed) = $crate::failures::malformed_response() {
+                    return Ok(malformed);
+                }
+                match msg {
+                    $($pattern => to_binary(&$response),)+
+                }
+            },
+        );
+        Box::new(contract) as Box<dyn Contract<Empty>>
+    }};
+}
@@ -0,0 +1,40 @@
+use cosmwasm_std::{Response, StdResult, Storage};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Every mock accepts this on migrate so admin migrate flows can be tested, the new version is stored and
+// reported by the mock's Version {} query
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {
+    pub version: String,
+}
+
+// The response that should be returned by the Version query of every mock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VersionResponse {
+    pub version: String,
+}
+
+pub const VERSION: Item<String> = Item::new("mock_version");
+
+// Shared migrate handler of the mocks, stores the version from the MigrateMsg.
+// The mocks don't implement an admin check of their own and don't store an admin: the migrate entrypoint isn't told
+// who sent the migration, so there would be nothing to compare a stored admin with. The App is what rejects
+// WasmMsg::Migrate from anyone but the admin given at instantiation, as wasmd does. Instantiate the mock with
+// `Some(admin)` to test admin-only migrations, a mock instantiated without an admin can't be migrated at all.
+pub fn migrate_version(storage: &mut dyn Storage, msg: MigrateMsg) -> StdResult<Response> {
+    VERSION.save(storage, &msg.version)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", msg.version))
+}
+
+// The stored version, a mock that was never migrated reports the crate version
+pub fn query_version(storage: &dyn Storage) -> StdResult<VersionResponse> {
+    let version = VERSION
+        .may_load(storage)?
+        .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string());
+    Ok(VersionResponse { version })
+}
@@ -0,0 +1,167 @@
+use cosmwasm_std::{to_binary, Binary, Decimal, Empty, Response, StdResult, Uint128};
+use lazy_static::lazy_static;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::errors::MockError;
+use crate::failures::{check_query_failure, malformed_response};
+use crate::lock::Lock;
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+
+// Decimal in this cosmwasm version has 18 fractional digits, the walk keeps its price as an integer of that precision
+const DECIMAL_FRACTIONAL: u128 = 1_000_000_000_000_000_000;
+// Resolution of each random step drawn from the PRNG
+const STEP_RESOLUTION: u64 = 1_000_000;
+
+// Internal state of a single price walk, advanced on every Price query for its pair
+#[derive(Debug, Clone)]
+struct PriceWalk {
+    price: Uint128,
+    state: u64,
+    volatility: Decimal,
+}
+
+impl PriceWalk {
+    // xorshift64, small and fully deterministic given the seed which is all we need for reproducible tests
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    // Move the price up or down by at most `volatility` of its current value
+    fn step(&mut self) -> StdResult<Decimal> {
+        let draw = self.next_random() % (2 * STEP_RESOLUTION + 1);
+        let movement = self.price * self.volatility;
+        if draw >= STEP_RESOLUTION {
+            self.price = self
+                .price
+                .checked_add(movement.multiply_ratio(draw - STEP_RESOLUTION, STEP_RESOLUTION))?;
+        } else {
+            self.price = self
+                .price
+                .saturating_sub(movement.multiply_ratio(STEP_RESOLUTION - draw, STEP_RESOLUTION));
+        }
+        Ok(Decimal::from_ratio(self.price, DECIMAL_FRACTIONAL))
+    }
+}
+
+// These lazy statics allow you the dev to set prices before you query the oracle mock so that prices can be mocked out as needed.
+lazy_static! {
+    static ref PRICES: Lock<HashMap<(String, String), Decimal>> = Lock::new(HashMap::new());
+    static ref PRICE_WALKS: Lock<HashMap<(String, String), PriceWalk>> = Lock::new(HashMap::new());
+}
+
+// Acquire a write lock on the price map and set a fixed price for the given pair
+pub fn set_price(base: String, quote: String, rate: Decimal) -> Decimal {
+    let mut prices = PRICES.write();
+    prices.insert((base, quote), rate);
+    return rate;
+}
+
+pub fn get_price(base: String, quote: String) -> Option<Decimal> {
+    return PRICES.read().get(&(base, quote)).cloned();
+}
+
+// Start a deterministic price walk for the given pair. Each Price query advances the walk by one step,
+// moving the price by at most `volatility` (e.g. Decimal::percent(5) for 5%) of its current value.
+// The walk starts from the price set with set_price, or 1 if none was set, and the same seed always yields the same sequence.
+pub fn set_price_walk(base: String, quote: String, seed: u64, volatility: Decimal) {
+    let start = get_price(base.clone(), quote.clone()).unwrap_or_else(Decimal::one);
+    let walk = PriceWalk {
+        price: Uint128::new(DECIMAL_FRACTIONAL) * start,
+        // xorshift gets stuck on a zero state so swap it for a fixed non-zero one
+        state: if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        },
+        volatility,
+    };
+    PRICE_WALKS.write().insert((base, quote), walk);
+}
+
+// Stop any price walk for the given pair, queries fall back to the fixed price
+pub fn clear_price_walk(base: String, quote: String) {
+    PRICE_WALKS.write().remove(&(base, quote));
+}
+
+// Forget every price and price walk, see reset_mock_state
+pub(crate) fn reset_state() {
+    PRICES.write().clear();
+    PRICE_WALKS.write().clear();
+}
+
+// Simple mocked instantiate with no params so devs can use it easily
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {}
+
+// The oracle mock has nothing to execute but the wrapper needs a message type
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {}
+
+// Mocked Query handler, a single Price query in the shape of the common price oracles on Terra
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    Price { base: String, quote: String },
+    Version {},
+}
+
+// The response that should be returned by the Price query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceResponse {
+    pub rate: Decimal,
+    pub last_updated_base: u64,
+    pub last_updated_quote: u64,
+}
+
+pub fn contract_oracle_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |_, _, _, msg: MockExecuteMsg| -> StdResult<Response> { match msg {} },
+        |_, _, _, _: MockInstantiateMsg| -> StdResult<Response> { Ok(Response::default()) },
+        |deps, env, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::Price { base, quote } => {
+                    let rate = mock_price(base, quote)?;
+                    Ok(to_binary(&PriceResponse {
+                        rate,
+                        last_updated_base: env.block.time.seconds(),
+                        last_updated_quote: env.block.time.seconds(),
+                    })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
+
+// Return the next price of the walk for the pair if there is one, otherwise the fixed price
+pub fn mock_price(base: String, quote: String) -> StdResult<Decimal> {
+    let key = (base, quote);
+    if let Some(walk) = PRICE_WALKS.write().get_mut(&key) {
+        return walk.step();
+    }
+    PRICES.read().get(&key).cloned().ok_or_else(|| {
+        MockError::NoPrice {
+            base: key.0.clone(),
+            quote: key.1.clone(),
+        }
+        .into()
+    })
+}
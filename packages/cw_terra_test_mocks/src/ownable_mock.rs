@@ -0,0 +1,138 @@
+use cosmwasm_std::{to_binary, Addr, Binary, Empty, Response, StdResult};
+use cw0::Expiration;
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::validation::validate_addr;
+
+// Mocked instantiate, the owner defaults to the instantiator when not given
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    pub owner: Option<String>,
+}
+
+// The ownership actions in the same shape as cw-ownable's Action
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Propose a new owner, who then has to accept before `expiry`
+    TransferOwnership {
+        new_owner: String,
+        expiry: Option<Expiration>,
+    },
+    /// Accept a pending ownership transfer, must be sent by the pending owner
+    AcceptOwnership,
+    /// Give up ownership for good, leaving the contract without an owner
+    RenounceOwnership,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    UpdateOwnership(Action),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    Ownership {},
+    Version {},
+}
+
+// The ownership state, also returned as-is by the Ownership query like cw-ownable does
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ownership {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+    pub pending_expiry: Option<Expiration>,
+}
+
+pub const OWNERSHIP: Item<Ownership> = Item::new("ownership");
+
+pub fn contract_ownable_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, env, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            let mut ownership = OWNERSHIP.load(deps.storage)?;
+            match msg {
+                MockExecuteMsg::UpdateOwnership(action) => {
+                    match action {
+                        Action::TransferOwnership { new_owner, expiry } => {
+                            if ownership.owner != Some(info.sender.clone()) {
+                                return Err(MockError::NotOwner {}.into());
+                            }
+                            if let Some(expiry) = &expiry {
+                                if expiry.is_expired(&env.block) {
+                                    return Err(MockError::ExpiryPassed {}.into());
+                                }
+                            }
+                            ownership.pending_owner = Some(validate_addr(deps.api, &new_owner)?);
+                            ownership.pending_expiry = expiry;
+                        }
+                        Action::AcceptOwnership => {
+                            if ownership.pending_owner != Some(info.sender.clone()) {
+                                return Err(MockError::NotPendingOwner {}.into());
+                            }
+                            if let Some(expiry) = &ownership.pending_expiry {
+                                if expiry.is_expired(&env.block) {
+                                    return Err(MockError::TransferExpired {}.into());
+                                }
+                            }
+                            ownership.owner = ownership.pending_owner.take();
+                            ownership.pending_expiry = None;
+                        }
+                        Action::RenounceOwnership => {
+                            if ownership.owner != Some(info.sender.clone()) {
+                                return Err(MockError::NotOwner {}.into());
+                            }
+                            ownership = Ownership {
+                                owner: None,
+                                pending_owner: None,
+                                pending_expiry: None,
+                            };
+                        }
+                    }
+                    OWNERSHIP.save(deps.storage, &ownership)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "update_ownership")
+                        .add_attribute("sender", info.sender))
+                }
+            }
+        },
+        |deps, _, info, msg: MockInstantiateMsg| -> StdResult<Response> {
+            let owner = match msg.owner {
+                Some(owner) => validate_addr(deps.api, &owner)?,
+                None => info.sender,
+            };
+            OWNERSHIP.save(
+                deps.storage,
+                &Ownership {
+                    owner: Some(owner),
+                    pending_owner: None,
+                    pending_expiry: None,
+                },
+            )?;
+            Ok(Response::default())
+        },
+        |deps, _, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::Ownership {} => Ok(to_binary(&OWNERSHIP.load(deps.storage)?)?),
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
@@ -0,0 +1,138 @@
+// A curated set of the types and helpers most tests need, `use cw_terra_test_mocks::prelude::*` pulls them all in.
+// Every mock names its messages MockExecuteMsg/MockQueryMsg so they are re-exported here under the mock's name.
+// The module paths (e.g. cw_terra_test_mocks::terraswap_mock) keep working as before.
+
+pub use crate::assertions::{
+    assert_attribute, assert_event, assert_msg_roundtrips, attribute_value,
+};
+pub use crate::assets::{asset, native, token};
+pub use crate::capabilities::{mock_capabilities, CapabilitiesResponse};
+pub use crate::errors::MockError;
+pub use crate::failures::{
+    clear_error_message, get_contract_unreachable, get_fail_every, get_fail_next_executes,
+    get_fail_next_queries, get_return_malformed, has_injected_error, set_contract_unreachable,
+    set_error_message, set_fail_every, set_fail_next_execute, set_fail_next_executes,
+    set_fail_next_queries, set_fail_next_query, set_injected_error, set_return_malformed,
+};
+pub use crate::helpers::{
+    advance_blocks, advance_time, predict_contract_address, MockAppBuilder, QueryExt,
+};
+pub use crate::migrate::{MigrateMsg, VersionResponse};
+pub use crate::reset::{reset_mock_state, MockGuard};
+pub use crate::trace::TraceResponse;
+pub use crate::validation::{get_strict_address_validation, set_strict_address_validation};
+
+#[cfg(feature = "terraswap")]
+pub use crate::assertions::{assert_k_preserved, assert_pool_approx_eq, assert_supply_consistent};
+#[cfg(feature = "terraswap")]
+pub use crate::client::TerraswapMockClient;
+#[cfg(feature = "terraswap")]
+pub use crate::helpers::{
+    drain_pool, dump_mock_config, instantiate2_terraswap_mock, load_mock_config_from_json,
+};
+#[cfg(feature = "terraswap")]
+pub use crate::querier::{
+    clear_querier_native_balances, get_querier_native_balance, mock_dependencies_with_terraswap,
+    set_querier_native_balance, WasmMockQuerier,
+};
+#[cfg(feature = "terraswap")]
+pub use crate::terraswap_mock::{
+    apply_swap, asset_infos_match, asset_to_coin, clear_fail_on, clear_locked,
+    clear_receive_response, clear_scheduled_reserves, clear_transfer_hook, coins_from_assets,
+    commission_rate, compute_swap, contract_terraswap_mock, contract_terraswap_mock_custom,
+    exchange_rate, expected_lp_mint, get_commission_split, get_cw20_paused,
+    get_force_swap_overflow, get_liq_token_addr, get_locked, get_minimum_liquidity,
+    get_pong_raw_bytes, get_query_lag, get_receive_response, get_reject_self_send,
+    get_reject_zero_return, get_reply_id, get_rounding, get_send_reply_on, get_transfer_hook,
+    get_treasury_addr, mock_balance_info, mock_pair_info, mock_pair_info_with, mock_pool_info,
+    mock_simulation_both, mock_token_info, pool_k, price_impact, provide_quote, schedule_reserves,
+    set_commission_split, set_cw20_paused, set_fail_on_execute, set_fail_on_query,
+    set_force_swap_overflow, set_liq_token_addr, set_locked, set_minimum_liquidity,
+    set_pong_raw_bytes, set_query_lag, set_receive_response, set_reject_self_send,
+    set_reject_zero_return, set_reply_id, set_rounding, set_send_reply_on, set_transfer_hook,
+    set_treasury_addr, simulate_swap, AssetsResponse, ConfigResponse as TerraswapConfigResponse,
+    DetailedBalanceResponse, ExchangeRateResponse, IsFrozenResponse, KEntry, KHistoryResponse,
+    MockConfig, MockExecuteMsg as TerraswapExecuteMsg,
+    MockExecuteMsgKind as TerraswapExecuteMsgKind, MockInstantiateMsg as TerraswapInstantiateMsg,
+    MockQueryMsg as TerraswapQueryMsg, MockQueryMsgKind as TerraswapQueryMsgKind,
+    MockSudoMsg as TerraswapSudoMsg, PairResponse, PingMsg, PoolResponse, PriceImpactResponse,
+    ProvideQuoteResponse, RoundingMode, Share, SimulationBothResponse, SwapResponse,
+    TransferHookMsg, TreasuryResponse, VolumeResponse, MAX_K_HISTORY, MAX_POOL_HISTORY,
+    RECEIVE_HOOK_SHAPES, SEND_REPLY_ID, SWAP_REPLY_ID,
+};
+
+#[cfg(feature = "router")]
+pub use crate::router_mock::{
+    contract_router_mock, simulate_operations, Cw20HookMsg as RouterCw20HookMsg,
+    MockExecuteMsg as RouterExecuteMsg, MockInstantiateMsg as RouterInstantiateMsg,
+    MockQueryMsg as RouterQueryMsg, SwapOperation,
+};
+
+#[cfg(feature = "factory")]
+pub use crate::factory_mock::{
+    contract_factory_mock, pair_key, MockExecuteMsg as FactoryExecuteMsg,
+    MockInstantiateMsg as FactoryInstantiateMsg, MockQueryMsg as FactoryQueryMsg, PairsResponse,
+};
+
+#[cfg(feature = "anchor")]
+pub use crate::anchor_mock::{
+    contract_anchor_mock, get_anchor_min_deposit, get_aust_addr, mock_epoch_state,
+    query_aust_exchange_rate, set_anchor_min_deposit, set_aust_addr, AnchorQuery,
+    EpochStateResponse, MockExecuteMsg as AnchorExecuteMsg,
+    MockInstantiateMsg as AnchorInstantiateMsg,
+};
+
+#[cfg(feature = "oracle")]
+pub use crate::oracle_mock::{
+    clear_price_walk, contract_oracle_mock, get_price, mock_price, set_price, set_price_walk,
+    MockInstantiateMsg as OracleInstantiateMsg, MockQueryMsg as OracleQueryMsg, PriceResponse,
+};
+
+#[cfg(feature = "lsd")]
+pub use crate::lsd_mock::{
+    contract_lsd_mock, get_lsd_exchange_rate, get_unbonding_period, set_lsd_exchange_rate,
+    set_unbonding_period, Cw20HookMsg as LsdHookMsg, MockExecuteMsg as LsdExecuteMsg,
+    MockInstantiateMsg as LsdInstantiateMsg, MockQueryMsg as LsdQueryMsg, StateResponse,
+    UnbondRequest, UnbondRequestsResponse,
+};
+
+#[cfg(feature = "ownable")]
+pub use crate::ownable_mock::{
+    contract_ownable_mock, Action, MockExecuteMsg as OwnableExecuteMsg,
+    MockInstantiateMsg as OwnableInstantiateMsg, MockQueryMsg as OwnableQueryMsg, Ownership,
+};
+
+#[cfg(feature = "staking")]
+pub use crate::staking_mock::{
+    clear_reward_price_oracle, compute_apr, contract_staking_mock, get_blocks_per_year,
+    get_reward_per_block, get_reward_price_oracle, get_staking_reward_rate, set_blocks_per_year,
+    set_reward_per_block, set_reward_price_oracle, set_staking_reward_rate, AprResponse,
+    Cw20HookMsg as StakingHookMsg, MockExecuteMsg as StakingExecuteMsg,
+    MockInstantiateMsg as StakingInstantiateMsg, MockQueryMsg as StakingQueryMsg,
+    StakerInfoResponse,
+};
+
+#[cfg(feature = "cw1")]
+pub use crate::cw1_whitelist_mock::{
+    contract_cw1_whitelist_mock, AdminListResponse, MockExecuteMsg as Cw1ExecuteMsg,
+    MockInstantiateMsg as Cw1InstantiateMsg, MockQueryMsg as Cw1QueryMsg,
+};
+
+#[cfg(feature = "cw3")]
+pub use crate::cw3_mock::{
+    clear_cw3_threshold, contract_cw3_mock, get_cw3_threshold, set_cw3_threshold,
+    MockExecuteMsg as Cw3ExecuteMsg, MockInstantiateMsg as Cw3InstantiateMsg,
+    MockQueryMsg as Cw3QueryMsg, ProposalResponse, Threshold, Voter,
+};
+
+#[cfg(feature = "cw4")]
+pub use crate::cw4_group_mock::{
+    contract_cw4_group_mock, MockExecuteMsg as Cw4ExecuteMsg,
+    MockInstantiateMsg as Cw4InstantiateMsg, MockQueryMsg as Cw4QueryMsg,
+};
+
+#[cfg(feature = "cw721")]
+pub use crate::cw721_mock::{
+    contract_cw721_mock, ApprovalsResponse, MockExecuteMsg as Cw721ExecuteMsg,
+    MockInstantiateMsg as Cw721InstantiateMsg, MockQueryMsg as Cw721QueryMsg,
+};
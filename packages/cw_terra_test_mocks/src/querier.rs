@@ -0,0 +1,146 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, AllBalanceResponse, BalanceResponse, BankQuery, Binary,
+    Coin, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError,
+    SystemResult, Uint128, WasmQuery,
+};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+use crate::capabilities::mock_capabilities;
+use crate::errors::MockError;
+use crate::failures::{check_query_failure, malformed_response};
+use crate::lock::Lock;
+use crate::terraswap_mock::{
+    mock_balance_info, mock_pair_info, mock_pool_info, mock_token_info, simulate_swap,
+    AssetsResponse, MockQueryMsg, PoolState,
+};
+
+// This lazy static use allows you the dev to set native balances the querier answers bank queries with
+lazy_static! {
+    // Accounts with an entry here are answered from it, the others fall through to the MockQuerier balances
+    static ref QUERIER_NATIVE_BALANCES: Lock<HashMap<String, Vec<Coin>>> = Lock::new(HashMap::new());
+}
+
+// Acquire a write lock on the static value and set the native coins `addr` holds for BankQuery::Balance/AllBalances
+pub fn set_querier_native_balance(addr: String, coins: Vec<Coin>) {
+    QUERIER_NATIVE_BALANCES.write().insert(addr, coins);
+}
+
+// Forget every balance set with set_querier_native_balance
+pub fn clear_querier_native_balances() {
+    QUERIER_NATIVE_BALANCES.write().clear();
+}
+
+pub fn get_querier_native_balance(addr: &str) -> Option<Vec<Coin>> {
+    return QUERIER_NATIVE_BALANCES.read().get(addr).cloned();
+}
+
+// mock_dependencies with a querier that answers smart queries to any contract with the terraswap mock responses,
+// for unit tests that don't spin up a multi-test App
+pub fn mock_dependencies_with_terraswap(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+// Intercepts WasmQuery::Smart carrying a terraswap MockQueryMsg and bank queries for accounts given a balance with
+// set_querier_native_balance, everything else goes to the cosmwasm MockQuerier
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier { base }
+    }
+
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: _,
+                msg,
+            }) => SystemResult::Ok(smart_query(msg).into()),
+            QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
+                match get_querier_native_balance(address) {
+                    // A denom the account doesn't hold is a zero balance, like the bank module answers
+                    Some(coins) => {
+                        let amount = coins
+                            .into_iter()
+                            .find(|coin| coin.denom == *denom)
+                            .unwrap_or_else(|| Coin {
+                                denom: denom.clone(),
+                                amount: Uint128::zero(),
+                            });
+                        SystemResult::Ok(to_binary(&BalanceResponse { amount }).into())
+                    }
+                    None => self.base.handle_query(request),
+                }
+            }
+            QueryRequest::Bank(BankQuery::AllBalances { address }) => {
+                match get_querier_native_balance(address) {
+                    Some(amount) => {
+                        SystemResult::Ok(to_binary(&AllBalanceResponse { amount }).into())
+                    }
+                    None => self.base.handle_query(request),
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+// The same failure toggles as the mock contract apply, the answers are the mock_* dummy responses since there is
+// no contract storage behind the querier
+fn smart_query(msg: &Binary) -> StdResult<Binary> {
+    check_query_failure()?;
+    if let Some(malformed) = malformed_response() {
+        return Ok(malformed);
+    }
+    match from_binary(msg)? {
+        MockQueryMsg::Pair { .. } => to_binary(&mock_pair_info()),
+        MockQueryMsg::Pool {} => to_binary(&mock_pool_info()),
+        MockQueryMsg::Assets {} => {
+            let [asset0, asset1] = mock_pool_info().assets;
+            to_binary(&AssetsResponse {
+                asset_infos: [asset0.info, asset1.info],
+            })
+        }
+        MockQueryMsg::TokenInfo {} => to_binary(&mock_token_info()),
+        MockQueryMsg::Balance { .. } => to_binary(&mock_balance_info()),
+        MockQueryMsg::Capabilities {} => to_binary(&mock_capabilities()),
+        MockQueryMsg::Simulation { offer_asset } => {
+            let pool_info = mock_pool_info();
+            let pool = PoolState {
+                assets: pool_info.assets,
+                total_share: pool_info.total_share.into(),
+            };
+            to_binary(&simulate_swap(&pool, &offer_asset)?)
+        }
+        other => Err(MockError::UnsupportedQuery {
+            kind: format!("{:?}", other.kind()),
+        }
+        .into()),
+    }
+}
@@ -0,0 +1,52 @@
+// The mocks keep their configuration in process wide statics, so whatever one test sets is still set for the next
+// test the same process runs, e.g. the address given to set_liq_token_addr. Which test sees which value then depends
+// on the order cargo test happens to run them in. Call reset_mock_state at the top of a test to start from the defaults.
+
+// Put the statics of every compiled mock, the failure toggles and the querier balances back to their defaults.
+// Tests run in parallel threads by default and share the statics, run with --test-threads=1 when tests set them.
+pub fn reset_mock_state() {
+    crate::failures::reset_state();
+    crate::validation::reset_state();
+    #[cfg(feature = "terraswap")]
+    {
+        crate::terraswap_mock::reset_state();
+        crate::querier::clear_querier_native_balances();
+    }
+    #[cfg(feature = "anchor")]
+    crate::anchor_mock::reset_state();
+    #[cfg(feature = "oracle")]
+    crate::oracle_mock::reset_state();
+    #[cfg(feature = "staking")]
+    crate::staking_mock::reset_state();
+    #[cfg(feature = "cw3")]
+    crate::cw3_mock::reset_state();
+    #[cfg(feature = "lsd")]
+    crate::lsd_mock::reset_state();
+}
+
+// Resets the mock state when created and again when dropped, so `let _guard = MockGuard::new();` at the top of a
+// test starts it from the defaults and cleans up after it even when it panics. Bind it to a name, `let _ = ...`
+// drops it straight away.
+#[must_use = "the state is reset when the guard is dropped, bind it with `let _guard`"]
+pub struct MockGuard {
+    _private: (),
+}
+
+impl MockGuard {
+    pub fn new() -> Self {
+        reset_mock_state();
+        MockGuard { _private: () }
+    }
+}
+
+impl Default for MockGuard {
+    fn default() -> Self {
+        MockGuard::new()
+    }
+}
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        reset_mock_state();
+    }
+}
@@ -0,0 +1,425 @@
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, Coin, CosmosMsg, Deps, Empty, Env, QueryRequest, ReplyOn,
+    Response, StdResult, SubMsg, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::{Cw20HookMsg as PairCw20HookMsg, SimulationResponse};
+use terraswap::router::SimulateSwapOperationsResponse;
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::terraswap_mock::{
+    asset_infos_match, MockExecuteMsg as PairExecuteMsg, MockQueryMsg as PairQueryMsg,
+    PoolResponse, SWAP_REPLY_ID,
+};
+use crate::validation::validate_addr;
+
+// Mocked instantiate, the terraswap mock pairs the router can route through
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    pub pairs: Vec<String>,
+}
+
+// One hop of a route, serialises exactly like Terraswap's router SwapOperation so production messages parse
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapOperation {
+    // A Terra market swap between two native denoms, accepted so real routes parse but not executed by the mock
+    NativeSwap {
+        offer_denom: String,
+        ask_denom: String,
+    },
+    TerraSwap {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
+}
+
+// Slimmed down Terraswap router ExecuteMsg. A native offer comes along as funds, a cw20 offer is sent with the
+// ExecuteSwapOperations hook. Like the real router every hop is its own ExecuteSwapOperation on the router.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    ExecuteSwapOperations {
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    },
+    /// Swap everything the router holds of the operation's offer, only the router itself can send it
+    ExecuteSwapOperation {
+        operation: SwapOperation,
+        to: Option<String>,
+    },
+    /// Error unless the receiver got at least minimum_receive since its balance was prev_balance
+    AssertMinimumReceive {
+        asset_info: AssetInfo,
+        prev_balance: Uint128,
+        minimum_receive: Uint128,
+        receiver: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    ExecuteSwapOperations {
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    },
+}
+
+// Mocked Query handler, only the simulation is needed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    SimulateSwapOperations {
+        offer_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+    Version {},
+}
+
+pub const PAIRS: Item<Vec<Addr>> = Item::new("pairs");
+
+pub fn contract_router_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, env, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            match msg {
+                MockExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender,
+                    amount,
+                    msg,
+                }) => {
+                    let Cw20HookMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive,
+                        to,
+                    } = from_binary(&msg)?;
+                    // The token that sent the hook has to be what the route starts with
+                    let expected = first_offer_info(&operations)?;
+                    let sent = AssetInfo::Token {
+                        contract_addr: info.sender.to_string(),
+                    };
+                    if expected != sent {
+                        return Err(MockError::OfferDenomMismatch {
+                            expected: expected.to_string(),
+                            received: sent.to_string(),
+                        }
+                        .into());
+                    }
+                    let sender = validate_addr(deps.api, &sender)?;
+                    execute_swap_operations(
+                        deps.as_ref(),
+                        &env,
+                        sender,
+                        amount,
+                        operations,
+                        minimum_receive,
+                        to,
+                    )
+                }
+                MockExecuteMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive,
+                    to,
+                } => {
+                    let offer_amount = native_offer_amount(&operations, &info.funds)?;
+                    execute_swap_operations(
+                        deps.as_ref(),
+                        &env,
+                        info.sender,
+                        offer_amount,
+                        operations,
+                        minimum_receive,
+                        to,
+                    )
+                }
+                MockExecuteMsg::ExecuteSwapOperation { operation, to } => {
+                    if info.sender != env.contract.address {
+                        return Err(MockError::Unauthorized {}.into());
+                    }
+                    let (offer_info, ask_info) = operation_infos(&operation)?;
+                    let pair = find_pair(deps.as_ref(), offer_info, ask_info)?;
+                    // Everything the router holds of the offer, what the hop before paid out to it
+                    let amount =
+                        query_asset_balance(deps.as_ref(), offer_info, &env.contract.address)?;
+                    let msg: CosmosMsg = match offer_info {
+                        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+                            contract_addr: pair.to_string(),
+                            funds: vec![Coin {
+                                denom: denom.to_string(),
+                                amount,
+                            }],
+                            msg: to_binary(&PairExecuteMsg::Swap {
+                                offer_asset: Asset {
+                                    info: offer_info.clone(),
+                                    amount,
+                                },
+                                belief_price: None,
+                                max_spread: None,
+                                to: to.clone(),
+                            })?,
+                        }
+                        .into(),
+                        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                            contract_addr: contract_addr.to_string(),
+                            funds: vec![],
+                            msg: to_binary(&Cw20ExecuteMsg::Send {
+                                contract: pair.to_string(),
+                                amount,
+                                msg: to_binary(&PairCw20HookMsg::Swap {
+                                    belief_price: None,
+                                    max_spread: None,
+                                    to: to.clone(),
+                                })?,
+                            })?,
+                        }
+                        .into(),
+                    };
+                    // Fire-and-forget, the id only labels the hop as a swap
+                    Ok(Response::new()
+                        .add_submessage(SubMsg {
+                            id: SWAP_REPLY_ID,
+                            msg,
+                            gas_limit: None,
+                            reply_on: ReplyOn::Never,
+                        })
+                        .add_attribute("action", "execute_swap_operation")
+                        .add_attribute("pair", pair)
+                        .add_attribute("offer_amount", amount))
+                }
+                MockExecuteMsg::AssertMinimumReceive {
+                    asset_info,
+                    prev_balance,
+                    minimum_receive,
+                    receiver,
+                } => {
+                    let receiver = validate_addr(deps.api, &receiver)?;
+                    let balance = query_asset_balance(deps.as_ref(), &asset_info, &receiver)?;
+                    let return_amount = balance.saturating_sub(prev_balance);
+                    if return_amount < minimum_receive {
+                        return Err(MockError::MinimumReceive {
+                            minimum_receive: minimum_receive.to_string(),
+                            return_amount: return_amount.to_string(),
+                        }
+                        .into());
+                    }
+                    Ok(Response::new().add_attribute("action", "assert_minimum_receive"))
+                }
+            }
+        },
+        |deps, _, _, msg: MockInstantiateMsg| -> StdResult<Response> {
+            let pairs = msg
+                .pairs
+                .iter()
+                .map(|pair| validate_addr(deps.api, pair))
+                .collect::<StdResult<Vec<Addr>>>()?;
+            PAIRS.save(deps.storage, &pairs)?;
+            Ok(Response::default())
+        },
+        |deps, _, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::SimulateSwapOperations {
+                    offer_amount,
+                    operations,
+                } => {
+                    let amount = simulate_operations(deps, offer_amount, &operations)?;
+                    Ok(to_binary(&SimulateSwapOperationsResponse { amount })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
+
+// Queue one ExecuteSwapOperation per hop on the router, the last one paying the receiver, and the minimum receive
+// check after them. The route is checked up front so a bad one errors before anything is swapped.
+fn execute_swap_operations(
+    deps: Deps,
+    env: &Env,
+    sender: Addr,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
+) -> StdResult<Response> {
+    simulate_operations(deps, offer_amount, &operations)?;
+    let receiver = match to {
+        Some(to) => validate_addr(deps.api, &to)?,
+        None => sender,
+    };
+    let last = operations.len() - 1;
+    let (_, final_ask_info) = operation_infos(&operations[last])?;
+    let final_ask_info = final_ask_info.clone();
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (i, operation) in operations.into_iter().enumerate() {
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&MockExecuteMsg::ExecuteSwapOperation {
+                    operation,
+                    to: if i == last {
+                        Some(receiver.to_string())
+                    } else {
+                        None
+                    },
+                })?,
+            }
+            .into(),
+        );
+    }
+    if let Some(minimum_receive) = minimum_receive {
+        let prev_balance = query_asset_balance(deps, &final_ask_info, &receiver)?;
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&MockExecuteMsg::AssertMinimumReceive {
+                    asset_info: final_ask_info,
+                    prev_balance,
+                    minimum_receive,
+                    receiver: receiver.to_string(),
+                })?,
+            }
+            .into(),
+        );
+    }
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "execute_swap_operations")
+        .add_attribute("receiver", receiver)
+        .add_attribute("offer_amount", offer_amount))
+}
+
+// The offer and ask of a hop, NativeSwap hops are Terra market swaps the mock doesn't execute
+fn operation_infos(operation: &SwapOperation) -> StdResult<(&AssetInfo, &AssetInfo)> {
+    match operation {
+        SwapOperation::TerraSwap {
+            offer_asset_info,
+            ask_asset_info,
+        } => Ok((offer_asset_info, ask_asset_info)),
+        SwapOperation::NativeSwap { .. } => Err(MockError::NativeSwapUnsupported {}.into()),
+    }
+}
+
+fn first_offer_info(operations: &[SwapOperation]) -> StdResult<AssetInfo> {
+    let first = operations.first().ok_or(MockError::NoOperations {})?;
+    Ok(operation_infos(first)?.0.clone())
+}
+
+// The amount of the coin the route starts with out of the funds sent, erroring if it wasn't sent
+fn native_offer_amount(operations: &[SwapOperation], funds: &[Coin]) -> StdResult<Uint128> {
+    let expected = first_offer_info(operations)?;
+    if funds.is_empty() {
+        return Err(MockError::NoOfferFunds {}.into());
+    }
+    funds
+        .iter()
+        .find(|coin| matches!(&expected, AssetInfo::NativeToken { denom } if *denom == coin.denom))
+        .map(|coin| coin.amount)
+        .ok_or_else(|| {
+            MockError::OfferDenomMismatch {
+                expected: expected.to_string(),
+                received: funds
+                    .iter()
+                    .map(|coin| coin.denom.clone())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            }
+            .into()
+        })
+}
+
+// The account's balance of a coin from the bank or of a token from its cw20 contract
+fn query_asset_balance(deps: Deps, info: &AssetInfo, account: &Addr) -> StdResult<Uint128> {
+    match info {
+        AssetInfo::NativeToken { denom } => Ok(deps
+            .querier
+            .query_balance(account.to_string(), denom)?
+            .amount),
+        AssetInfo::Token { contract_addr } => {
+            let balance: BalanceResponse = deps.querier.query_wasm_smart(
+                contract_addr,
+                &Cw20QueryMsg::Balance {
+                    address: account.to_string(),
+                },
+            )?;
+            Ok(balance.balance)
+        }
+    }
+}
+
+// Find the registered pair trading the two assets
+fn find_pair(deps: Deps, offer_info: &AssetInfo, ask_info: &AssetInfo) -> StdResult<Addr> {
+    let wanted = [offer_info.clone(), ask_info.clone()];
+    for pair in PAIRS.load(deps.storage)? {
+        let pool: PoolResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pair.to_string(),
+            msg: to_binary(&PairQueryMsg::Pool {})?,
+        }))?;
+        let pool_infos = [pool.assets[0].info.clone(), pool.assets[1].info.clone()];
+        if asset_infos_match(&pool_infos, &wanted) {
+            return Ok(pair);
+        }
+    }
+    Err(MockError::NoPairForOperation {
+        offer: offer_info.to_string(),
+        ask: ask_info.to_string(),
+    }
+    .into())
+}
+
+// Walk the operations through the registered pairs with their Simulation query and return what the last hop pays out
+pub fn simulate_operations(
+    deps: Deps,
+    offer_amount: Uint128,
+    operations: &[SwapOperation],
+) -> StdResult<Uint128> {
+    if operations.is_empty() {
+        return Err(MockError::NoOperations {}.into());
+    }
+    let mut amount = offer_amount;
+    let mut previous_ask: Option<AssetInfo> = None;
+    for operation in operations {
+        let (offer_asset_info, ask_asset_info) = operation_infos(operation)?;
+        // Each hop has to offer what the one before it returned
+        if let Some(previous_ask) = &previous_ask {
+            if previous_ask != offer_asset_info {
+                return Err(MockError::DisconnectedOperations {}.into());
+            }
+        }
+        let pair = find_pair(deps, offer_asset_info, ask_asset_info)?;
+        // The pair simulates with its own commission, which may differ from the other hops'
+        let simulation: SimulationResponse = deps.querier.query_wasm_smart(
+            pair,
+            &PairQueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: offer_asset_info.clone(),
+                    amount,
+                },
+            },
+        )?;
+        amount = simulation.return_amount;
+        previous_ask = Some(ask_asset_info.clone());
+    }
+    Ok(amount)
+}
@@ -0,0 +1,355 @@
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, Empty, Env, Response,
+    StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Item, Map};
+use lazy_static::lazy_static;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{Contract, ContractWrapper};
+
+use crate::errors::MockError;
+use crate::failures::{check_execute_failure, check_query_failure, malformed_response};
+use crate::lock::Lock;
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::oracle_mock::{MockQueryMsg as OracleQueryMsg, PriceResponse};
+use crate::validation::validate_addr;
+
+// Decimal in this cosmwasm version has 18 fractional digits, used to scale block counts without losing precision
+const DECIMAL_FRACTIONAL: u128 = 1_000_000_000_000_000_000;
+
+// This lazy static use allows you the dev to set the reward rate before you use the staking mock so that farming rewards can be mocked out as needed.
+lazy_static! {
+    // Reward paid per bonded token for every block it stays bonded
+    static ref REWARD_RATE: Lock<Decimal> = Lock::new(Decimal::zero());
+    // Total reward emitted every block, split between stakers pro-rata to their bond
+    static ref REWARD_PER_BLOCK: Lock<Uint128> = Lock::new(Uint128::zero());
+    // Used to annualise the per block rewards in the Apr query, roughly Terra's ~6.8s blocks
+    static ref BLOCKS_PER_YEAR: Lock<u64> = Lock::new(4_656_810);
+    // The oracle mock and the base/quote pair the Apr query prices the reward token in staking tokens with.
+    // Without one the reward token is valued 1:1 with the staking token.
+    static ref REWARD_PRICE_ORACLE: Lock<Option<(String, String, String)>> = Lock::new(None);
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_staking_reward_rate(new_rate: Decimal) -> Decimal {
+    let mut rate = REWARD_RATE.write();
+    *rate = new_rate;
+    return *rate;
+}
+
+pub fn get_staking_reward_rate() -> Decimal {
+    return *REWARD_RATE.read();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_reward_per_block(new_reward: Uint128) -> Uint128 {
+    let mut reward = REWARD_PER_BLOCK.write();
+    *reward = new_reward;
+    return *reward;
+}
+
+pub fn get_reward_per_block() -> Uint128 {
+    return *REWARD_PER_BLOCK.read();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_blocks_per_year(new_blocks: u64) -> u64 {
+    let mut blocks = BLOCKS_PER_YEAR.write();
+    *blocks = new_blocks;
+    return *blocks;
+}
+
+pub fn get_blocks_per_year() -> u64 {
+    return *BLOCKS_PER_YEAR.read();
+}
+
+// Acquire a write lock on the static value and point the Apr query at the Price {base, quote} of an oracle mock
+pub fn set_reward_price_oracle(oracle: String, base: String, quote: String) {
+    *REWARD_PRICE_ORACLE.write() = Some((oracle, base, quote));
+}
+
+// Go back to valuing the reward token 1:1 with the staking token
+pub fn clear_reward_price_oracle() {
+    *REWARD_PRICE_ORACLE.write() = None;
+}
+
+pub fn get_reward_price_oracle() -> Option<(String, String, String)> {
+    return REWARD_PRICE_ORACLE.read().clone();
+}
+
+// Put the statics back to the defaults above, see reset_mock_state
+pub(crate) fn reset_state() {
+    set_staking_reward_rate(Decimal::zero());
+    set_reward_per_block(Uint128::zero());
+    set_blocks_per_year(4_656_810);
+    clear_reward_price_oracle();
+}
+
+// Mocked instantiate, the LP token that can be bonded and the token rewards are paid in
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    pub staking_token: String,
+    pub reward_token: String,
+}
+
+// Slimmed down LP staking ExecuteMsg, bonding happens through a cw20 Send of the staking token
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    Unbond { amount: Uint128 },
+    Withdraw {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Bond the sent staking tokens for the sender
+    Bond {},
+}
+
+// Mocked Query handler, only StakerInfo is needed to check on a position
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    StakerInfo { staker: String },
+    Apr {},
+    Version {},
+}
+
+// The response that should be returned by the StakerInfo query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakerInfoResponse {
+    pub staker: String,
+    pub reward_index: Decimal,
+    pub bond_amount: Uint128,
+    pub pending_reward: Uint128,
+}
+
+// The response that should be returned by the Apr query, 0.12 is a 12% yearly return
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AprResponse {
+    pub apr: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub staking_token: Addr,
+    pub reward_token: Addr,
+}
+
+// Rewards are tracked with a global index that grows every block, each staker remembers the index they last settled at
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct State {
+    pub global_index: Decimal,
+    pub total_bond_amount: Uint128,
+    pub last_distributed: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct StakerInfo {
+    pub reward_index: Decimal,
+    pub bond_amount: Uint128,
+    pub pending_reward: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const STATE: Item<State> = Item::new("state");
+pub const STAKERS: Map<&Addr, StakerInfo> = Map::new("stakers");
+
+// Grow the global index by the rewards of the blocks since it was last updated.
+// A staker with `bond` out of `total_bond_amount` earns blocks * reward_per_block * bond / total_bond_amount
+// on top of blocks * reward_rate * bond.
+fn compute_reward(state: &mut State, block_height: u64) -> StdResult<()> {
+    let blocks = block_height.saturating_sub(state.last_distributed);
+    let per_token = Uint128::new(DECIMAL_FRACTIONAL * blocks as u128) * get_staking_reward_rate();
+    state.global_index = state.global_index + Decimal::from_ratio(per_token, DECIMAL_FRACTIONAL);
+    // Nothing is bonded so the emission for these blocks goes to nobody
+    if !state.total_bond_amount.is_zero() {
+        let emitted = get_reward_per_block().checked_mul(Uint128::from(blocks))?;
+        state.global_index =
+            state.global_index + Decimal::from_ratio(emitted, state.total_bond_amount);
+    }
+    state.last_distributed = block_height;
+    Ok(())
+}
+
+// Move the rewards earned since the staker's last update into their pending rewards
+fn compute_staker_reward(state: &State, staker_info: &mut StakerInfo) -> StdResult<()> {
+    let pending = (staker_info.bond_amount * state.global_index)
+        .checked_sub(staker_info.bond_amount * staker_info.reward_index)?;
+    staker_info.reward_index = state.global_index;
+    staker_info.pending_reward = staker_info.pending_reward.checked_add(pending)?;
+    Ok(())
+}
+
+// Load the state and the staker's info with rewards settled up to the current block
+fn settle(storage: &dyn Storage, env: &Env, staker: &Addr) -> StdResult<(State, StakerInfo)> {
+    let mut state = STATE.load(storage)?;
+    compute_reward(&mut state, env.block.height)?;
+    let mut staker_info = STAKERS.may_load(storage, staker)?.unwrap_or_default();
+    compute_staker_reward(&state, &mut staker_info)?;
+    Ok((state, staker_info))
+}
+
+fn cw20_transfer(token: &Addr, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+    }))
+}
+
+pub fn contract_staking_mock() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, env, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            let config = CONFIG.load(deps.storage)?;
+            match msg {
+                MockExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender,
+                    amount,
+                    msg,
+                }) => match from_binary(&msg)? {
+                    Cw20HookMsg::Bond {} => {
+                        // Only the staking token can be bonded
+                        if info.sender != config.staking_token {
+                            return Err(MockError::Unauthorized {}.into());
+                        }
+                        let staker = validate_addr(deps.api, &sender)?;
+                        let (mut state, mut staker_info) = settle(deps.storage, &env, &staker)?;
+                        staker_info.bond_amount = staker_info.bond_amount.checked_add(amount)?;
+                        state.total_bond_amount = state.total_bond_amount.checked_add(amount)?;
+                        STATE.save(deps.storage, &state)?;
+                        STAKERS.save(deps.storage, &staker, &staker_info)?;
+                        Ok(Response::new()
+                            .add_attribute("action", "bond")
+                            .add_attribute("staker", staker)
+                            .add_attribute("amount", amount))
+                    }
+                },
+                MockExecuteMsg::Unbond { amount } => {
+                    let (mut state, mut staker_info) = settle(deps.storage, &env, &info.sender)?;
+                    staker_info.bond_amount = staker_info.bond_amount.checked_sub(amount)?;
+                    state.total_bond_amount = state.total_bond_amount.checked_sub(amount)?;
+                    STATE.save(deps.storage, &state)?;
+                    STAKERS.save(deps.storage, &info.sender, &staker_info)?;
+                    Ok(Response::new()
+                        .add_message(cw20_transfer(&config.staking_token, &info.sender, amount)?)
+                        .add_attribute("action", "unbond")
+                        .add_attribute("staker", info.sender)
+                        .add_attribute("amount", amount))
+                }
+                MockExecuteMsg::Withdraw {} => {
+                    let (state, mut staker_info) = settle(deps.storage, &env, &info.sender)?;
+                    let amount = staker_info.pending_reward;
+                    staker_info.pending_reward = Uint128::zero();
+                    STATE.save(deps.storage, &state)?;
+                    STAKERS.save(deps.storage, &info.sender, &staker_info)?;
+                    let mut response = Response::new();
+                    // cw20 tokens refuse zero transfers so only pay out when something was earned
+                    if !amount.is_zero() {
+                        response = response.add_message(cw20_transfer(
+                            &config.reward_token,
+                            &info.sender,
+                            amount,
+                        )?);
+                    }
+                    Ok(response
+                        .add_attribute("action", "withdraw")
+                        .add_attribute("staker", info.sender)
+                        .add_attribute("amount", amount))
+                }
+            }
+        },
+        |deps, env, _, msg: MockInstantiateMsg| -> StdResult<Response> {
+            CONFIG.save(
+                deps.storage,
+                &Config {
+                    staking_token: validate_addr(deps.api, &msg.staking_token)?,
+                    reward_token: validate_addr(deps.api, &msg.reward_token)?,
+                },
+            )?;
+            STATE.save(
+                deps.storage,
+                &State {
+                    global_index: Decimal::zero(),
+                    total_bond_amount: Uint128::zero(),
+                    last_distributed: env.block.height,
+                },
+            )?;
+            Ok(Response::default())
+        },
+        |deps, env, msg: MockQueryMsg| -> StdResult<Binary> {
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            match msg {
+                MockQueryMsg::StakerInfo { staker } => {
+                    Ok(to_binary(&query_staker_info(deps, &env, staker)?)?)
+                }
+                MockQueryMsg::Apr {} => {
+                    let state = STATE.load(deps.storage)?;
+                    let price = query_reward_token_price(deps)?;
+                    Ok(to_binary(&AprResponse {
+                        apr: compute_apr(state.total_bond_amount, price)?,
+                    })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
+            }
+        },
+    )
+    .with_migrate(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
+    Box::new(contract)
+}
+
+// Return the staker's position with rewards accrued up to the current block
+pub fn query_staker_info(deps: Deps, env: &Env, staker: String) -> StdResult<StakerInfoResponse> {
+    let staker_addr = validate_addr(deps.api, &staker)?;
+    let (_, staker_info) = settle(deps.storage, env, &staker_addr)?;
+    Ok(StakerInfoResponse {
+        staker,
+        reward_index: staker_info.reward_index,
+        bond_amount: staker_info.bond_amount,
+        pending_reward: staker_info.pending_reward,
+    })
+}
+
+// The reward token price in staking tokens, read from the oracle mock set with set_reward_price_oracle
+fn query_reward_token_price(deps: Deps) -> StdResult<Decimal> {
+    match get_reward_price_oracle() {
+        Some((oracle, base, quote)) => {
+            let price: PriceResponse = deps
+                .querier
+                .query_wasm_smart(oracle, &OracleQueryMsg::Price { base, quote })?;
+            Ok(price.rate)
+        }
+        None => Ok(Decimal::one()),
+    }
+}
+
+// The yearly reward per bonded token valued in staking tokens: a year of the per token reward rate plus a year of the
+// per block emission shared over `total_bond_amount`, times the reward token price. With nothing bonded only the
+// reward rate counts, the emission has nobody to go to.
+pub fn compute_apr(total_bond_amount: Uint128, reward_token_price: Decimal) -> StdResult<Decimal> {
+    let blocks_per_year = get_blocks_per_year();
+    let rate_per_year =
+        Uint128::new(DECIMAL_FRACTIONAL * blocks_per_year as u128) * get_staking_reward_rate();
+    let mut apr = Decimal::from_ratio(rate_per_year, DECIMAL_FRACTIONAL);
+    if !total_bond_amount.is_zero() {
+        let emitted_per_year =
+            get_reward_per_block().checked_mul(Uint128::from(blocks_per_year))?;
+        apr = apr + Decimal::from_ratio(emitted_per_year, total_bond_amount);
+    }
+    let valued = Uint128::new(DECIMAL_FRACTIONAL) * apr * reward_token_price;
+    Ok(Decimal::from_ratio(valued, DECIMAL_FRACTIONAL))
+}
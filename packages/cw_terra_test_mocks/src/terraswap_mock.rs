@@ -1,24 +1,116 @@
-use cosmwasm_std::{from_binary, to_binary, Addr, Binary, Empty, Response, StdResult, Uint128};
-use cw20::Cw20ReceiveMsg;
-use cw20::{BalanceResponse, TokenInfoResponse};
-use cw_storage_plus::Map;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Attribute, BankMsg, Binary, Coin, ContractResult, CosmosMsg,
+    Decimal, DepsMut, Empty, Env, Order, OverflowError, OverflowOperation, Reply, ReplyOn,
+    Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{AllAccountsResponse, BalanceResponse, TokenInfoResponse};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, Item, Map, U64Key};
 use lazy_static::lazy_static;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::sync::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
 use terra_multi_test::{Contract, ContractWrapper};
 use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::{Cw20HookMsg, SimulationResponse};
+
+use crate::capabilities::mock_capabilities;
+use crate::errors::MockError;
+use crate::failures::{
+    check_execute_failure, check_query_failure, malformed_response, mock_failure,
+};
+use crate::lock::Lock;
+use crate::migrate::{migrate_version, query_version, MigrateMsg};
+use crate::trace::{log_query, query_trace, record_trace};
+use crate::validation::validate_addr;
+
+// Stable ids for the submessages the mocks dispatch, match on these in reply handlers to tell the operations apart.
+// The router mock dispatches its hops with SWAP_REPLY_ID, consumer contracts swapping on the pair can use it too.
+pub const SWAP_REPLY_ID: u64 = 1;
+pub const SEND_REPLY_ID: u64 = 2;
+
+// Same pagination limits cw20-base uses for AllAccounts
+const DEFAULT_LIMIT: u32 = 10;
+pub(crate) const MAX_LIMIT: u32 = 30;
+
+// The JSON shapes the Receive hook understands, listed in the error for any other payload
+pub const RECEIVE_HOOK_SHAPES: &[&str] = &[
+    r#"{"payload":"<string>"}"#,
+    r#"{"swap":{"belief_price":null,"max_spread":null,"to":null}}"#,
+];
+
+// Only the latest k values are kept, the oldest is dropped once a swap would record one more
+pub const MAX_K_HISTORY: u64 = 1000;
+
+// Past pool states kept for set_query_lag, enough for any lag a test is likely to use
+pub const MAX_POOL_HISTORY: usize = 100;
 
 // This lazy static use allows you the dev to set the aust token addr before you use the anchor mock so that you can mock out AUST as needed.
 lazy_static! {
     // This lazily made static uses a ReadWrite lock to ensure some form of safety on setting/getting values and means you dont need to wrap the code in an unsafe block which looks icky
-    static ref TOKEN_ADDR: RwLock<String> = RwLock::new("string".to_string());
+    static ref TOKEN_ADDR: Lock<String> = Lock::new("string".to_string());
+    // How swap outputs are rounded, Floor by default to match Terraswap
+    static ref ROUNDING: Lock<RoundingMode> = Lock::new(RoundingMode::Floor);
+    // The kinds of message that keep failing until cleared, for testing partial failures
+    static ref FAIL_ON_EXECUTE: Lock<HashSet<MockExecuteMsgKind>> = Lock::new(HashSet::new());
+    static ref FAIL_ON_QUERY: Lock<HashSet<MockQueryMsgKind>> = Lock::new(HashSet::new());
+    // The swap commission in bps as (kept by LPs, paid to the protocol), 0.3% all to LPs by default like Terraswap
+    static ref COMMISSION_SPLIT: Lock<(u16, u16)> = Lock::new((30, 0));
+    // Where the protocol part of the commission is said to go, reported by the Treasury query
+    static ref TREASURY_ADDR: Lock<String> = Lock::new("treasury".to_string());
+    // Whether the swap math overflows no matter the inputs
+    static ref FORCE_SWAP_OVERFLOW: Lock<bool> = Lock::new(false);
+    // Reserve changes that only show once the chain reaches the given height, as (seq, height, reserves) by pair
+    // address. They stay here once applied, the pair's SCHEDULE_APPLIED tells whether its pool already has the change.
+    static ref SCHEDULED_RESERVES: Lock<HashMap<String, (u64, u64, [Asset; 2])>> = Lock::new(HashMap::new());
+    // The seq of the last reserve change scheduled, so a change scheduled again gets applied again
+    static ref SCHEDULE_SEQ: Lock<u64> = Lock::new(0);
+    // The id Send dispatches the receive hook with and the reply entrypoint answers to, replies with any other id error
+    static ref REPLY_ID: Lock<u64> = Lock::new(SEND_REPLY_ID);
+    // When Send asks for a reply to the receive hook it dispatches, Never keeps it fire-and-forget
+    static ref SEND_REPLY_ON: Lock<ReplyOn> = Lock::new(ReplyOn::Never);
+    // Whether Send, Transfer and TransferFrom to the token's own address error, like tokens that guard against stuck funds
+    static ref REJECT_SELF_SEND: Lock<bool> = Lock::new(false);
+    // LP shares locked away on the first deposit into an empty pool so it can never be fully drained, like Uniswap
+    static ref MINIMUM_LIQUIDITY: Lock<Uint128> = Lock::new(Uint128::new(1000));
+    // Whether the pong echoes the received msg bytes as they are instead of the payload as a JSON string
+    static ref PONG_RAW_BYTES: Lock<bool> = Lock::new(false);
+    // Whether a swap whose return amount truncates to zero errors like on a real pair, instead of paying out nothing
+    static ref REJECT_ZERO_RETURN: Lock<bool> = Lock::new(true);
+    // What the Receive hook answers with instead of the pong, as (attributes, data)
+    static ref RECEIVE_RESPONSE: Lock<Option<(Vec<Attribute>, Option<Binary>)>> = Lock::new(None);
+    // Contract notified of every Transfer, TransferFrom and Send like cw20-hooks tokens do, none by default
+    static ref TRANSFER_HOOK: Lock<Option<String>> = Lock::new(None);
+    // Part of an account's balance that is vesting or locked and can't be moved, by account address
+    static ref LOCKED: Lock<HashMap<String, Uint128>> = Lock::new(HashMap::new());
+    // How many blocks behind the chain the Pool query answers, 0 answers with the live pool
+    static ref QUERY_LAG: Lock<u64> = Lock::new(0);
+    // Whether the token's transfers are switched off like a token with a tripped circuit breaker
+    static ref CW20_PAUSED: Lock<bool> = Lock::new(false);
 }
 
-// Simple mocked instantiate with no params so devs can use it easily 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+// The direction swap and simulation outputs are rounded in when the curve doesn't land on a whole amount
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct MockInstantiateMsg {}
+pub enum RoundingMode {
+    /// Truncate the output, what Terraswap does
+    Floor,
+    /// Round the output up to the next whole amount
+    Ceil,
+    /// Round the output to the closest whole amount, halves round up
+    Nearest,
+}
+
+// Simple mocked instantiate so devs can use it easily, `MockInstantiateMsg::default()` trades the assets of
+// mock_pool_info. Give asset_infos to trade other assets with the same reserves, e.g. for the pairs of a route.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MockInstantiateMsg {
+    #[serde(default)]
+    pub asset_infos: Option<[AssetInfo; 2]>,
+}
 
 // PingMsg used to give you a quick helper for Receive operations
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -27,7 +119,18 @@ pub struct PingMsg {
     pub payload: String,
 }
 
-// Mocked ExecuteMsg with some CW20 related functions, maybe these are needed at all but it gives you a bigger mock to play with. 
+// The message the transfer hook receiver is executed with after tokens moved, see set_transfer_hook
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferHookMsg {
+    TransferHook {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+    },
+}
+
+// Mocked ExecuteMsg with some CW20 related functions, maybe these are needed at all but it gives you a bigger mock to play with.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MockExecuteMsg {
@@ -48,6 +151,150 @@ pub enum MockExecuteMsg {
         recipient: String,
         amount: Uint128,
     },
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    // Frozen accounts can't send or receive tokens, like a permissioned token would enforce
+    FreezeAccount {
+        addr: String,
+    },
+    UnfreezeAccount {
+        addr: String,
+    },
+    ProvideLiquidity {
+        assets: [Asset; 2],
+    },
+    WithdrawLiquidity {
+        amount: Share,
+    },
+    // belief_price and max_spread are accepted so real swap messages parse, the spread isn't asserted.
+    // Sent along with exactly the offered coin the pair pays the return out to `to`, without funds the swap only
+    // moves the reserves. Cw20 offers are paid for by a Send with the swap hook instead.
+    Swap {
+        offer_asset: Asset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    // Scale every balance and the total supply by `factor` like an elastic supply token's rebase, factors stack up
+    Rebase {
+        factor: Decimal,
+    },
+    // Start counting the Volume query from zero again
+    ResetVolume {},
+}
+
+// Privileged messages only the chain (e.g. governance) can send, dispatched with app.wasm_sudo in tests
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockSudoMsg {
+    // Set the whole swap commission of this pair, the protocol keeps its part of it as long as it fits.
+    // Other pairs keep theirs, see set_commission_split for the default of the pairs never updated.
+    UpdateConfig { commission_rate: Decimal },
+    // Replace the pool, commission, token info and balances of this pair with the fixture, see load_mock_config_from_json
+    LoadConfig { config: MockConfig },
+}
+
+// The kind of a MockExecuteMsg without its fields, used to make only one kind of message fail
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockExecuteMsgKind {
+    Receive,
+    Mint,
+    Send,
+    Burn,
+    Transfer,
+    TransferFrom,
+    FreezeAccount,
+    UnfreezeAccount,
+    ProvideLiquidity,
+    WithdrawLiquidity,
+    Swap,
+    Rebase,
+    ResetVolume,
+}
+
+impl MockExecuteMsg {
+    pub fn kind(&self) -> MockExecuteMsgKind {
+        match self {
+            MockExecuteMsg::Receive(_) => MockExecuteMsgKind::Receive,
+            MockExecuteMsg::Mint { .. } => MockExecuteMsgKind::Mint,
+            MockExecuteMsg::Send { .. } => MockExecuteMsgKind::Send,
+            MockExecuteMsg::Burn { .. } => MockExecuteMsgKind::Burn,
+            MockExecuteMsg::Transfer { .. } => MockExecuteMsgKind::Transfer,
+            MockExecuteMsg::TransferFrom { .. } => MockExecuteMsgKind::TransferFrom,
+            MockExecuteMsg::FreezeAccount { .. } => MockExecuteMsgKind::FreezeAccount,
+            MockExecuteMsg::UnfreezeAccount { .. } => MockExecuteMsgKind::UnfreezeAccount,
+            MockExecuteMsg::ProvideLiquidity { .. } => MockExecuteMsgKind::ProvideLiquidity,
+            MockExecuteMsg::WithdrawLiquidity { .. } => MockExecuteMsgKind::WithdrawLiquidity,
+            MockExecuteMsg::Swap { .. } => MockExecuteMsgKind::Swap,
+            MockExecuteMsg::Rebase { .. } => MockExecuteMsgKind::Rebase,
+            MockExecuteMsg::ResetVolume {} => MockExecuteMsgKind::ResetVolume,
+        }
+    }
+}
+
+// An amount of LP shares in the pool. Kept apart from Uint128 so shares can't be mixed up with asset amounts
+#[derive(
+    Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+#[serde(transparent)]
+pub struct Share(pub Uint128);
+
+impl Share {
+    pub fn zero() -> Self {
+        Share(Uint128::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn checked_add(self, other: Share) -> StdResult<Share> {
+        Ok(Share(self.0.checked_add(other.0)?))
+    }
+
+    pub fn checked_sub(self, other: Share) -> StdResult<Share> {
+        Ok(Share(self.0.checked_sub(other.0)?))
+    }
+
+    // The part of `amount` this share is entitled to out of `total` shares
+    pub fn portion_of(self, amount: Uint128, total: Share) -> StdResult<Uint128> {
+        if total.is_zero() {
+            return Err(MockError::PoolHasNoShares {}.into());
+        }
+        Ok(amount.multiply_ratio(self.0, total.0))
+    }
+}
+
+impl From<Uint128> for Share {
+    fn from(amount: Uint128) -> Self {
+        Share(amount)
+    }
+}
+
+impl From<Share> for Uint128 {
+    fn from(share: Share) -> Self {
+        share.0
+    }
+}
+
+// The pool reserves and LP supply kept in storage, seeded from mock_pool_info on instantiate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolState {
+    pub assets: [Asset; 2],
+    pub total_share: Share,
+}
+
+impl From<PoolState> for PoolResponse {
+    fn from(pool: PoolState) -> Self {
+        PoolResponse {
+            assets: pool.assets,
+            total_share: pool.total_share.into(),
+        }
+    }
 }
 
 // We define a custom struct for each query response
@@ -69,78 +316,1047 @@ pub struct PairResponse {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MockQueryMsg {
-    Pair {},
+    // asset_infos is optional, when given the pair errors unless they match its own assets
+    Pair {
+        #[serde(default)]
+        asset_infos: Option<[AssetInfo; 2]>,
+    },
     Pool {},
+    // Just the two asset infos the pool trades, in pool order
+    Assets {},
     TokenInfo {},
-    Balance { address: String },
+    Balance {
+        address: String,
+    },
+    // The balance split into the part that can be moved and the part locked with set_locked
+    DetailedBalance {
+        address: String,
+    },
+    // The accounts holding a stored balance, always in ascending address order whatever order they were credited in
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Capabilities {},
+    // The protocol fees collected by swaps so far, see set_commission_split
+    Treasury {},
+    // The commission this pair swaps with, see MockSudoMsg::UpdateConfig
+    Config {},
+    IsFrozen {
+        addr: String,
+    },
+    Simulation {
+        offer_asset: Asset,
+    },
+    // How far the marginal price of the pool would move if offer_asset was swapped, see price_impact
+    PriceImpact {
+        offer_asset: Asset,
+    },
+    // What one unit of offer_asset_info marginally buys of the other asset after commission, see exchange_rate
+    ExchangeRate {
+        offer_asset_info: AssetInfo,
+    },
+    // Simulates offering `offer_amount` of each side of the pool, see mock_simulation_both
+    SimulationBoth {
+        offer_amount: Uint128,
+    },
+    // The shares a ProvideLiquidity of `deposits` would mint and the part of them it wouldn't use, see provide_quote
+    ProvideQuote {
+        deposits: [Asset; 2],
+    },
+    // The env.block the mock sees, to check the App's block advanced as expected in time dependent tests
+    BlockInfo {},
+    // What hit the mock so far, only recorded with the trace feature on
+    Trace {},
+    // Everything swapped since instantiation or the last ResetVolume
+    Volume {},
+    // The constant product k recorded after each swap, oldest first, see MAX_K_HISTORY
+    KHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Version {},
+}
+
+// The kind of a MockQueryMsg without its fields, used to make only one kind of query fail
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsgKind {
+    Pair,
+    Pool,
+    Assets,
+    TokenInfo,
+    Balance,
+    DetailedBalance,
+    AllAccounts,
+    Capabilities,
+    Version,
+    Treasury,
+    Config,
+    IsFrozen,
+    Simulation,
+    PriceImpact,
+    ExchangeRate,
+    SimulationBoth,
+    ProvideQuote,
+    BlockInfo,
+    Trace,
+    Volume,
+    KHistory,
+}
+
+impl MockQueryMsg {
+    pub fn kind(&self) -> MockQueryMsgKind {
+        match self {
+            MockQueryMsg::Pair { .. } => MockQueryMsgKind::Pair,
+            MockQueryMsg::Pool {} => MockQueryMsgKind::Pool,
+            MockQueryMsg::Assets {} => MockQueryMsgKind::Assets,
+            MockQueryMsg::TokenInfo {} => MockQueryMsgKind::TokenInfo,
+            MockQueryMsg::Balance { .. } => MockQueryMsgKind::Balance,
+            MockQueryMsg::DetailedBalance { .. } => MockQueryMsgKind::DetailedBalance,
+            MockQueryMsg::AllAccounts { .. } => MockQueryMsgKind::AllAccounts,
+            MockQueryMsg::Capabilities {} => MockQueryMsgKind::Capabilities,
+            MockQueryMsg::Version {} => MockQueryMsgKind::Version,
+            MockQueryMsg::Treasury {} => MockQueryMsgKind::Treasury,
+            MockQueryMsg::Config {} => MockQueryMsgKind::Config,
+            MockQueryMsg::IsFrozen { .. } => MockQueryMsgKind::IsFrozen,
+            MockQueryMsg::Simulation { .. } => MockQueryMsgKind::Simulation,
+            MockQueryMsg::PriceImpact { .. } => MockQueryMsgKind::PriceImpact,
+            MockQueryMsg::ExchangeRate { .. } => MockQueryMsgKind::ExchangeRate,
+            MockQueryMsg::SimulationBoth { .. } => MockQueryMsgKind::SimulationBoth,
+            MockQueryMsg::ProvideQuote { .. } => MockQueryMsgKind::ProvideQuote,
+            MockQueryMsg::BlockInfo {} => MockQueryMsgKind::BlockInfo,
+            MockQueryMsg::Trace {} => MockQueryMsgKind::Trace,
+            MockQueryMsg::Volume {} => MockQueryMsgKind::Volume,
+            MockQueryMsg::KHistory { .. } => MockQueryMsgKind::KHistory,
+        }
+    }
+}
+
+// Set as the data of every Swap response so a contract dispatching the swap as a submessage can read it in its reply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapResponse {
+    pub offer_asset: Asset,
+    pub return_asset: Asset,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+}
+
+// The response returned by the Treasury query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TreasuryResponse {
+    pub treasury: String,
+    pub fees: Vec<Asset>,
+}
+
+// The response returned by the Assets query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetsResponse {
+    pub asset_infos: [AssetInfo; 2],
+}
+
+// The response returned by the DetailedBalance query, available and locked add up to the Balance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DetailedBalanceResponse {
+    pub available: Uint128,
+    pub locked: Uint128,
+}
+
+// The response returned by the Config query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub commission_rate: Decimal,
+    // (lp_bps, protocol_bps), see set_commission_split
+    pub commission_split: (u16, u16),
+}
+
+// A snapshot of what a terraswap mock is currently set up with, Debug print it or to_binary it for JSON
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct MockConfig {
+    pub pool: PoolResponse,
+    pub commission_rate: Decimal,
+    // (lp_bps, protocol_bps), see set_commission_split
+    pub commission_split: (u16, u16),
+    pub token_info: TokenInfoResponse,
+    // Only the accounts with a tracked balance, as (account, balance)
+    pub balances: Vec<(String, Uint128)>,
+}
+
+// The response returned by the IsFrozen query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IsFrozenResponse {
+    pub frozen: bool,
+}
+
+// The response returned by the PriceImpact query, 0.05 means the price moves by 5%
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceImpactResponse {
+    pub price_impact: Decimal,
+}
+
+// The response returned by the ExchangeRate query, in units of the asked asset per unit of the offered one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    pub exchange_rate: Decimal,
+}
+
+// The response returned by the SimulationBoth query, one SimulationResponse per swap direction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulationBothResponse {
+    pub forward: SimulationResponse,
+    pub reverse: SimulationResponse,
+}
+
+// The response returned by the ProvideQuote query, refund_assets are in the pool's asset order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProvideQuoteResponse {
+    pub share: Uint128,
+    pub refund_assets: [Asset; 2],
+}
+
+// The response returned by the Volume query, the amounts offered to and paid out by swaps per asset
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct VolumeResponse {
+    pub offer_volume: Vec<Asset>,
+    pub ask_volume: Vec<Asset>,
+}
+
+// One k value of the KHistory query, seq counts the swaps from 1
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KEntry {
+    pub seq: u64,
+    pub height: u64,
+    pub k: Uint256,
+}
+
+// The response returned by the KHistory query, fees stay in the pool so k should never go down between entries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KHistoryResponse {
+    pub entries: Vec<KEntry>,
 }
 
 pub const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+pub const POOL: Item<PoolState> = Item::new("pool");
+// The asset infos given at instantiation, the Pair query reports mock_pair_info's when there are none
+pub const ASSET_INFOS: Item<[AssetInfo; 2]> = Item::new("asset_infos");
+pub const SHARES: Map<&Addr, Share> = Map::new("shares");
+// Accounts are unfrozen unless they have an entry here
+pub const FROZEN: Map<&Addr, bool> = Map::new("frozen");
+pub const PROTOCOL_FEES: Item<Vec<Asset>> = Item::new("protocol_fees");
+// The (lp_bps, protocol_bps) commission of this pair once set by the UpdateConfig sudo, set_commission_split's before
+pub const COMMISSION: Item<(u16, u16)> = Item::new("commission");
+pub const VOLUME: Item<VolumeResponse> = Item::new("volume");
+// The pool as it was after each block that changed it, as (height, pool), oldest first
+pub const POOL_HISTORY: Item<Vec<(u64, PoolState)>> = Item::new("pool_history");
+pub const K_SEQ: Item<u64> = Item::new("k_seq");
+pub const K_HISTORY: Map<U64Key, KEntry> = Map::new("k_history");
+// The seq of the scheduled reserve change last written into POOL, saved with the pool so a reverted execute leaves
+// the change pending, see schedule_reserves
+pub const SCHEDULE_APPLIED: Item<u64> = Item::new("schedule_applied");
+// Stored (unrebased) supply, only tracked once something is minted. Until then TokenInfo answers the dummy supply.
+pub const TOTAL_SUPPLY: Item<Uint128> = Item::new("total_supply");
+// The token name, symbol and decimals loaded with MockSudoMsg::LoadConfig, mock_token_info's until then
+pub const TOKEN_INFO: Item<TokenInfoResponse> = Item::new("token_info");
+// Balances are stored unscaled and multiplied by this at query time, so a rebase doesn't rewrite every entry
+pub const REBASE_FACTOR: Item<Decimal256> = Item::new("rebase_factor");
+
+// Add the asset to the running total of its kind, starting a new total for an asset not seen before
+fn add_to_totals(totals: &mut Vec<Asset>, asset: &Asset) -> StdResult<()> {
+    match totals.iter_mut().find(|total| total.info == asset.info) {
+        Some(total) => total.amount = total.amount.checked_add(asset.amount)?,
+        None => totals.push(asset.clone()),
+    }
+    Ok(())
+}
+
+fn rebase_factor(storage: &dyn Storage) -> StdResult<Decimal256> {
+    Ok(REBASE_FACTOR
+        .may_load(storage)?
+        .unwrap_or_else(Decimal256::one))
+}
+
+// The stored amount for a token amount moved after the rebases so far
+fn to_stored_amount(storage: &dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    Ok((Uint256::from(amount) / rebase_factor(storage)?).into())
+}
+
+// Add `amount` to the account's stored balance
+fn credit(storage: &mut dyn Storage, account: &Addr, amount: Uint128) -> StdResult<()> {
+    let stored = to_stored_amount(storage, amount)?;
+    BALANCES.update(
+        storage,
+        account,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_add(stored)?)
+        },
+    )?;
+    Ok(())
+}
+
+// Take `amount` off the account's balance, erroring if it holds less. Accounts nothing was ever credited to hold the
+// dummy balance, it is checked like any other and stored once debited so they can't move more than it.
+fn debit(storage: &mut dyn Storage, account: &Addr, amount: Uint128) -> StdResult<()> {
+    let balance = balance_of(storage, account)?;
+    if amount > balance {
+        return Err(MockError::InsufficientBalance {
+            balance: balance.to_string(),
+            amount: amount.to_string(),
+        }
+        .into());
+    }
+    let stored_balance = BALANCES
+        .may_load(storage, account)?
+        .unwrap_or_else(|| mock_balance_info().balance);
+    let stored = to_stored_amount(storage, amount)?;
+    BALANCES.save(storage, account, &stored_balance.checked_sub(stored)?)?;
+    Ok(())
+}
+
+// Grow the tracked supply by a minted amount, starting the tracking on the first mint
+fn increase_supply(storage: &mut dyn Storage, amount: Uint128) -> StdResult<()> {
+    let stored = to_stored_amount(storage, amount)?;
+    let supply = TOTAL_SUPPLY.may_load(storage)?.unwrap_or_default();
+    TOTAL_SUPPLY.save(storage, &supply.checked_add(stored)?)?;
+    Ok(())
+}
+
+// Shrink the tracked supply by a burnt amount, the dummy supply is left alone when nothing was minted yet
+fn decrease_supply(storage: &mut dyn Storage, amount: Uint128) -> StdResult<()> {
+    let stored = to_stored_amount(storage, amount)?;
+    if let Some(supply) = TOTAL_SUPPLY.may_load(storage)? {
+        TOTAL_SUPPLY.save(storage, &supply.checked_sub(stored)?)?;
+    }
+    Ok(())
+}
+
+// The token amount a stored amount is worth after the rebases so far
+fn to_rebased_amount(storage: &dyn Storage, stored: Uint128) -> StdResult<Uint128> {
+    Ok((Uint256::from(stored) * rebase_factor(storage)?).into())
+}
+
+// The account's balance after the rebases so far, accounts nothing was moved to hold the dummy balance
+fn balance_of(storage: &dyn Storage, account: &Addr) -> StdResult<Uint128> {
+    let stored = BALANCES
+        .may_load(storage, account)?
+        .unwrap_or_else(|| mock_balance_info().balance);
+    to_rebased_amount(storage, stored)
+}
+
+// Error if moving `amount` out of the account would dip into the part locked with set_locked
+fn assert_available(storage: &dyn Storage, account: &Addr, amount: Uint128) -> StdResult<()> {
+    let locked = get_locked(account.as_str());
+    if locked.is_zero() {
+        return Ok(());
+    }
+    let available = balance_of(storage, account)?.saturating_sub(locked);
+    if amount > available {
+        return Err(MockError::BalanceLocked {
+            amount: amount.to_string(),
+            available: available.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+// Record the pool's k after a swap, dropping the oldest entry past MAX_K_HISTORY
+fn record_k(storage: &mut dyn Storage, height: u64, pool: &PoolState) -> StdResult<()> {
+    let seq = K_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    K_SEQ.save(storage, &seq)?;
+    let k = pool_k(pool);
+    K_HISTORY.save(storage, seq.into(), &KEntry { seq, height, k })?;
+    if seq > MAX_K_HISTORY {
+        K_HISTORY.remove(storage, (seq - MAX_K_HISTORY).into());
+    }
+    Ok(())
+}
+
+// Save the pool and remember it as the state of this block for lagged Pool queries, see set_query_lag.
+// The pool was loaded with any scheduled change that is due, so that change is marked as written along with it.
+fn save_pool(storage: &mut dyn Storage, env: &Env, pool: &PoolState) -> StdResult<()> {
+    let height = env.block.height;
+    if let Some((seq, _)) = due_schedule(storage, env)? {
+        SCHEDULE_APPLIED.save(storage, &seq)?;
+    }
+    POOL.save(storage, pool)?;
+    let mut history = POOL_HISTORY.may_load(storage)?.unwrap_or_default();
+    // Only the last change of a block is what the block ends with
+    if matches!(history.last(), Some((last_height, _)) if *last_height == height) {
+        history.pop();
+    }
+    history.push((height, pool.clone()));
+    if history.len() > MAX_POOL_HISTORY {
+        history.remove(0);
+    }
+    POOL_HISTORY.save(storage, &history)
+}
+
+// The pool as it was `lag` blocks before `height`. Lags reaching past the history answer with the oldest state kept.
+fn lagged_pool(storage: &dyn Storage, height: u64, lag: u64) -> StdResult<PoolState> {
+    let target = height.saturating_sub(lag);
+    let history = POOL_HISTORY.load(storage)?;
+    let pool = history
+        .iter()
+        .rev()
+        .find(|(changed_at, _)| *changed_at <= target)
+        .or_else(|| history.first())
+        .map(|(_, pool)| pool.clone());
+    match pool {
+        Some(pool) => Ok(pool),
+        None => POOL.load(storage),
+    }
+}
+
+// The reserve change scheduled for this pair that is due at this block and not in its stored pool yet, as (seq, reserves)
+fn due_schedule(storage: &dyn Storage, env: &Env) -> StdResult<Option<(u64, [Asset; 2])>> {
+    let due = match SCHEDULED_RESERVES.read().get(env.contract.address.as_str()) {
+        Some((seq, at_height, reserves)) if env.block.height >= *at_height => {
+            (*seq, reserves.clone())
+        }
+        _ => return Ok(None),
+    };
+    if SCHEDULE_APPLIED.may_load(storage)? == Some(due.0) {
+        return Ok(None);
+    }
+    Ok(Some(due))
+}
+
+// Set the pool's reserves to the scheduled change that is due, if any
+fn apply_due_schedule(storage: &dyn Storage, env: &Env, pool: &mut PoolState) -> StdResult<()> {
+    if let Some((_, reserves)) = due_schedule(storage, env)? {
+        let amounts = deposit_amounts(pool, &reserves)?;
+        for (pool_asset, amount) in pool.assets.iter_mut().zip(amounts.iter()) {
+            pool_asset.amount = *amount;
+        }
+    }
+    Ok(())
+}
+
+// Load the pool with any scheduled reserve change for this pair that is due applied, see schedule_reserves.
+// The change is only marked as written by save_pool, so queries keep applying it until an execute saves the pool.
+fn load_pool(storage: &dyn Storage, env: &Env) -> StdResult<PoolState> {
+    let mut pool = POOL.load(storage)?;
+    apply_due_schedule(storage, env, &mut pool)?;
+    Ok(pool)
+}
+
+// Error if any of the accounts taking part in a token movement is frozen
+fn assert_not_frozen(storage: &dyn Storage, accounts: &[&Addr]) -> StdResult<()> {
+    for account in accounts {
+        if FROZEN.may_load(storage, account)?.unwrap_or(false) {
+            return Err(MockError::AccountFrozen {
+                account: account.to_string(),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+// Error on any token movement while set_cw20_paused is on
+fn assert_not_paused() -> StdResult<()> {
+    if get_cw20_paused() {
+        return Err(MockError::TokenPaused {}.into());
+    }
+    Ok(())
+}
+
+// Error on tokens sent to the token itself when set_reject_self_send is on
+fn assert_not_self(token: &Addr, recipient: &Addr) -> StdResult<()> {
+    if get_reject_self_send() && token == recipient {
+        return Err(MockError::SelfSend {}.into());
+    }
+    Ok(())
+}
+
+// Add the execute of the transfer hook receiver to the response when one is set. It runs after the tokens moved,
+// so the receiver sees the new balances, and an error in it reverts the whole transfer.
+fn with_transfer_hook(
+    response: Response,
+    sender: &str,
+    recipient: &str,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let hook = match get_transfer_hook() {
+        Some(hook) => hook,
+        None => return Ok(response),
+    };
+    Ok(response.add_message(WasmMsg::Execute {
+        contract_addr: hook,
+        funds: vec![],
+        msg: to_binary(&TransferHookMsg::TransferHook {
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+    }))
+}
+
+// The message paying `amount` of the asset to the receiver, a bank send for a coin and a cw20 Transfer for a token
+fn payout_msg(info: &AssetInfo, amount: Uint128, receiver: &Addr) -> StdResult<CosmosMsg> {
+    Ok(match info {
+        AssetInfo::NativeToken { denom } => BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: vec![Coin {
+                denom: denom.to_string(),
+                amount,
+            }],
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: receiver.to_string(),
+                amount,
+            })?,
+        }
+        .into(),
+    })
+}
+
+// Swap the offer against the pool for `sender`, shared by the Swap message and the cw20 swap hook.
+// With `pay_out` the return is sent to the receiver, otherwise only the reserves move.
+fn execute_swap(
+    deps: DepsMut,
+    env: &Env,
+    sender: Addr,
+    offer_asset: Asset,
+    to: Option<String>,
+    pay_out: bool,
+) -> StdResult<Response> {
+    let mut pool = load_pool(deps.storage, env)?;
+    let offer = offer_index(&pool, &offer_asset.info)?;
+    let ask_info = pool.assets[1 - offer].info.clone();
+    let split = pair_commission_split(deps.storage)?;
+    let (swap, protocol_fee) =
+        swap_reserves(&mut pool, &offer_asset, split, get_reject_zero_return())?;
+    save_pool(deps.storage, env, &pool)?;
+    record_k(deps.storage, env.block.height, &pool)?;
+    if !protocol_fee.amount.is_zero() {
+        let mut fees = PROTOCOL_FEES.may_load(deps.storage)?.unwrap_or_default();
+        add_to_totals(&mut fees, &protocol_fee)?;
+        PROTOCOL_FEES.save(deps.storage, &fees)?;
+    }
+    let mut volume = VOLUME.may_load(deps.storage)?.unwrap_or_default();
+    add_to_totals(&mut volume.offer_volume, &offer_asset)?;
+    add_to_totals(
+        &mut volume.ask_volume,
+        &Asset {
+            info: ask_info.clone(),
+            amount: swap.return_amount,
+        },
+    )?;
+    VOLUME.save(deps.storage, &volume)?;
+    let receiver = match to {
+        Some(to) => validate_addr(deps.api, &to)?,
+        None => sender.clone(),
+    };
+    let swap_response = SwapResponse {
+        offer_asset: offer_asset.clone(),
+        return_asset: Asset {
+            info: ask_info.clone(),
+            amount: swap.return_amount,
+        },
+        spread_amount: swap.spread_amount,
+        commission_amount: swap.commission_amount,
+    };
+    let mut response = Response::new();
+    if pay_out && !swap.return_amount.is_zero() {
+        response = response.add_message(payout_msg(&ask_info, swap.return_amount, &receiver)?);
+    }
+    // The protocol part of the commission leaves with the swap too. A fee in the mock's own token moves between the
+    // stored balances, any other asset is sent to the treasury like the return is to the receiver.
+    if pay_out && !protocol_fee.amount.is_zero() {
+        let treasury = Addr::unchecked(get_treasury_addr());
+        match &protocol_fee.info {
+            AssetInfo::Token { contract_addr }
+                if *contract_addr == env.contract.address.as_str() =>
+            {
+                debit(deps.storage, &env.contract.address, protocol_fee.amount)?;
+                credit(deps.storage, &treasury, protocol_fee.amount)?;
+            }
+            info => {
+                response = response.add_message(payout_msg(info, protocol_fee.amount, &treasury)?);
+            }
+        }
+    }
+    Ok(response
+        .set_data(to_binary(&swap_response)?)
+        .add_attribute("action", "swap")
+        .add_attribute("sender", sender)
+        .add_attribute("receiver", receiver)
+        .add_attribute("offer_asset", offer_asset.info.to_string())
+        .add_attribute("ask_asset", ask_info.to_string())
+        .add_attribute("offer_amount", offer_asset.amount)
+        .add_attribute("return_amount", swap.return_amount)
+        .add_attribute("spread_amount", swap.spread_amount)
+        .add_attribute("commission_amount", swap.commission_amount)
+        .add_attribute("protocol_fee_amount", protocol_fee.amount))
+}
 
 pub fn contract_terraswap_mock() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        |deps, _, info, msg: MockExecuteMsg| -> StdResult<Response> {
+    return contract_terraswap_mock_custom::<Empty>();
+}
+
+// The same mock for an App with a custom message type, e.g. TerraMsgWrapper. The handlers only ever return
+// Response<Empty> which the wrapper converts to Response<C>.
+pub fn contract_terraswap_mock_custom<C>() -> Box<dyn Contract<C>>
+where
+    C: Clone + Debug + PartialEq + JsonSchema + 'static,
+{
+    let contract = ContractWrapper::new_with_empty(
+        |deps, env, info, msg: MockExecuteMsg| -> StdResult<Response> {
+            check_execute_failure()?;
+            check_execute_kind_failure(msg.kind())?;
+            record_trace(deps.storage, env.block.height, &info, &msg)?;
             match msg {
                 MockExecuteMsg::Receive(Cw20ReceiveMsg {
-                    sender: _,
-                    amount: _,
+                    sender,
+                    amount,
                     msg,
                 }) => {
-                    let received: PingMsg = from_binary(&msg)?;
+                    // No payload means the tokens were just deposited, there is no hook to answer
+                    if msg.is_empty() {
+                        return Ok(Response::new()
+                            .add_attribute("action", "deposit")
+                            .add_attribute("amount", amount));
+                    }
+                    // The swap hook of a real pair, the sending token has to be one of the pool's assets
+                    if let Ok(Cw20HookMsg::Swap { to, .. }) = from_binary(&msg) {
+                        let offer_asset = Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: info.sender.to_string(),
+                            },
+                            amount,
+                        };
+                        let trader = validate_addr(deps.api, &sender)?;
+                        return execute_swap(deps, &env, trader, offer_asset, to, true);
+                    }
+                    // A configured response answers any hook payload, not just a PingMsg
+                    if let Some((attributes, data)) = get_receive_response() {
+                        let response = Response::new().add_attributes(attributes);
+                        return Ok(match data {
+                            Some(data) => response.set_data(data),
+                            None => response,
+                        });
+                    }
+                    let received: PingMsg = match from_binary(&msg) {
+                        Ok(received) => received,
+                        Err(_) => {
+                            return Err(MockError::UnknownReceiveHook {
+                                received: String::from_utf8_lossy(msg.as_slice()).to_string(),
+                                expected: RECEIVE_HOOK_SHAPES.join(", "),
+                            }
+                            .into())
+                        }
+                    };
+                    let data = if get_pong_raw_bytes() {
+                        msg
+                    } else {
+                        to_binary(&received.payload)?
+                    };
                     Ok(Response::new()
                         .add_attribute("action", "pong")
-                        .set_data(to_binary(&received.payload)?))
+                        .set_data(data))
+                }
+                MockExecuteMsg::Mint { recipient, amount } => {
+                    let rcpt_addr = validate_addr(deps.api, &recipient)?;
+                    credit(deps.storage, &rcpt_addr, amount)?;
+                    increase_supply(deps.storage, amount)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "mint")
+                        .add_attribute("to", recipient)
+                        .add_attribute("amount", amount))
                 }
-                MockExecuteMsg::Mint {
-                    recipient: _,
-                    amount: _,
-                } => Ok(Response::new()),
                 MockExecuteMsg::Send {
                     contract,
                     amount,
                     msg,
-                } => Ok(Response::new().add_message(
-                    Cw20ReceiveMsg {
-                        sender: info.sender.into(),
-                        amount,
-                        msg,
+                } => {
+                    let contract_addr = validate_addr(deps.api, &contract)?;
+                    assert_not_paused()?;
+                    assert_not_self(&env.contract.address, &contract_addr)?;
+                    assert_not_frozen(deps.storage, &[&info.sender, &contract_addr])?;
+                    assert_available(deps.storage, &info.sender, amount)?;
+                    // Only the sent amount moves, the rest of the sender's balance stays put
+                    debit(deps.storage, &info.sender, amount)?;
+                    credit(deps.storage, &contract_addr, amount)?;
+                    // A Send without a payload is a plain deposit, skip the hook
+                    if msg.is_empty() {
+                        let response = Response::new()
+                            .add_attribute("action", "send")
+                            .add_attribute("from", info.sender.clone())
+                            .add_attribute("to", contract.clone())
+                            .add_attribute("amount", amount);
+                        return with_transfer_hook(
+                            response,
+                            info.sender.as_str(),
+                            &contract,
+                            amount,
+                        );
                     }
-                    .into_cosmos_msg(contract)?,
-                )),
-                MockExecuteMsg::Burn { amount: _ } => Ok(Response::new()),
+                    // Dispatched with the reply id so the receiver's reply can be told apart, see set_send_reply_on
+                    let response = Response::new().add_submessage(SubMsg {
+                        id: get_reply_id(),
+                        msg: Cw20ReceiveMsg {
+                            sender: info.sender.to_string(),
+                            amount,
+                            msg,
+                        }
+                        .into_cosmos_msg(contract.clone())?,
+                        gas_limit: None,
+                        reply_on: get_send_reply_on(),
+                    });
+                    with_transfer_hook(response, info.sender.as_str(), &contract, amount)
+                }
+                MockExecuteMsg::Burn { amount } => {
+                    assert_not_frozen(deps.storage, &[&info.sender])?;
+                    assert_available(deps.storage, &info.sender, amount)?;
+                    debit(deps.storage, &info.sender, amount)?;
+                    decrease_supply(deps.storage, amount)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "burn")
+                        .add_attribute("from", info.sender)
+                        .add_attribute("amount", amount))
+                }
                 MockExecuteMsg::Transfer { recipient, amount } => {
-                    let rcpt_addr = deps.api.addr_validate(&recipient)?;
-                    BALANCES.update(
+                    let rcpt_addr = validate_addr(deps.api, &recipient)?;
+                    assert_not_paused()?;
+                    assert_not_self(&env.contract.address, &rcpt_addr)?;
+                    assert_not_frozen(deps.storage, &[&info.sender, &rcpt_addr])?;
+                    assert_available(deps.storage, &info.sender, amount)?;
+                    debit(deps.storage, &info.sender, amount)?;
+                    credit(deps.storage, &rcpt_addr, amount)?;
+                    let response = Response::new()
+                        .add_attribute("action", "transfer")
+                        .add_attribute("from", info.sender.clone())
+                        .add_attribute("to", recipient.clone())
+                        .add_attribute("amount", amount);
+                    with_transfer_hook(response, info.sender.as_str(), &recipient, amount)
+                }
+                MockExecuteMsg::TransferFrom {
+                    owner,
+                    recipient,
+                    amount,
+                } => {
+                    let owner_addr = validate_addr(deps.api, &owner)?;
+                    let rcpt_addr = validate_addr(deps.api, &recipient)?;
+                    assert_not_paused()?;
+                    assert_not_self(&env.contract.address, &rcpt_addr)?;
+                    assert_not_frozen(deps.storage, &[&owner_addr, &rcpt_addr])?;
+                    assert_available(deps.storage, &owner_addr, amount)?;
+                    debit(deps.storage, &owner_addr, amount)?;
+                    credit(deps.storage, &rcpt_addr, amount)?;
+                    let response = Response::new()
+                        .add_attribute("action", "transfer_from")
+                        .add_attribute("from", owner.clone())
+                        .add_attribute("to", recipient.clone())
+                        .add_attribute("by", info.sender)
+                        .add_attribute("amount", amount);
+                    with_transfer_hook(response, &owner, &recipient, amount)
+                }
+                MockExecuteMsg::FreezeAccount { addr } => {
+                    let account = validate_addr(deps.api, &addr)?;
+                    FROZEN.save(deps.storage, &account, &true)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "freeze_account")
+                        .add_attribute("account", account))
+                }
+                MockExecuteMsg::UnfreezeAccount { addr } => {
+                    let account = validate_addr(deps.api, &addr)?;
+                    FROZEN.remove(deps.storage, &account);
+                    Ok(Response::new()
+                        .add_attribute("action", "unfreeze_account")
+                        .add_attribute("account", account))
+                }
+                MockExecuteMsg::ProvideLiquidity { assets } => {
+                    let mut pool = load_pool(deps.storage, &env)?;
+                    let share = provide_liquidity(&mut pool, &assets)?;
+                    save_pool(deps.storage, &env, &pool)?;
+                    SHARES.update(
                         deps.storage,
-                        &rcpt_addr,
-                        |balance: Option<Uint128>| -> StdResult<_> {
-                            Ok(balance.unwrap_or_default() + amount)
+                        &info.sender,
+                        |shares: Option<Share>| -> StdResult<_> {
+                            shares.unwrap_or_default().checked_add(share)
                         },
                     )?;
                     Ok(Response::new()
-                        .add_attribute("action", "transfer")
-                        .add_attribute("from", info.sender)
-                        .add_attribute("to", recipient)
-                        .add_attribute("amount", amount))
+                        .add_attribute("action", "provide_liquidity")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("assets", format!("{}, {}", assets[0], assets[1]))
+                        .add_attribute("share", Uint128::from(share)))
+                }
+                MockExecuteMsg::WithdrawLiquidity { amount } => {
+                    SHARES.update(
+                        deps.storage,
+                        &info.sender,
+                        |shares: Option<Share>| -> StdResult<_> {
+                            shares.unwrap_or_default().checked_sub(amount)
+                        },
+                    )?;
+                    let mut pool = load_pool(deps.storage, &env)?;
+                    let refund_assets = withdraw_liquidity(&mut pool, amount)?;
+                    save_pool(deps.storage, &env, &pool)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "withdraw_liquidity")
+                        .add_attribute("sender", info.sender)
+                        .add_attribute("withdrawn_share", Uint128::from(amount))
+                        .add_attribute(
+                            "refund_assets",
+                            format!("{}, {}", refund_assets[0], refund_assets[1]),
+                        ))
+                }
+                MockExecuteMsg::Swap {
+                    offer_asset,
+                    belief_price: _,
+                    max_spread: _,
+                    to,
+                } => {
+                    let paid = match (&offer_asset.info, info.funds.as_slice()) {
+                        (_, []) => false,
+                        (AssetInfo::NativeToken { denom }, [coin])
+                            if coin.denom == *denom && coin.amount == offer_asset.amount =>
+                        {
+                            true
+                        }
+                        (_, funds) => {
+                            return Err(MockError::OfferFundsMismatch {
+                                offer: offer_asset.to_string(),
+                                funds: funds
+                                    .iter()
+                                    .map(|coin| format!("{}{}", coin.amount, coin.denom))
+                                    .collect::<Vec<String>>()
+                                    .join(", "),
+                            }
+                            .into())
+                        }
+                    };
+                    execute_swap(deps, &env, info.sender, offer_asset, to, paid)
+                }
+                MockExecuteMsg::Rebase { factor } => {
+                    if factor.is_zero() {
+                        return Err(MockError::InvalidRebaseFactor {}.into());
+                    }
+                    let rebase_factor = rebase_factor(deps.storage)? * Decimal256::from(factor);
+                    REBASE_FACTOR.save(deps.storage, &rebase_factor)?;
+                    Ok(Response::new()
+                        .add_attribute("action", "rebase")
+                        .add_attribute("factor", factor.to_string())
+                        .add_attribute("rebase_factor", rebase_factor.to_string()))
+                }
+                MockExecuteMsg::ResetVolume {} => {
+                    VOLUME.remove(deps.storage);
+                    Ok(Response::new().add_attribute("action", "reset_volume"))
                 }
             }
         },
-        |_, _, _, _: MockInstantiateMsg| -> StdResult<Response> { Ok(Response::default()) },
-        |_, _, msg: MockQueryMsg| -> StdResult<Binary> {
+        |deps, env, _, msg: MockInstantiateMsg| -> StdResult<Response> {
+            let mut pool_info = mock_pool_info();
+            if let Some(asset_infos) = msg.asset_infos {
+                for (pool_asset, info) in pool_info.assets.iter_mut().zip(asset_infos.iter()) {
+                    pool_asset.info = info.clone();
+                }
+                ASSET_INFOS.save(deps.storage, &asset_infos)?;
+            }
+            let mut pool = PoolState {
+                assets: pool_info.assets,
+                total_share: pool_info.total_share.into(),
+            };
+            // A change scheduled for the address before the pair existed applies from the start
+            apply_due_schedule(deps.storage, &env, &mut pool)?;
+            save_pool(deps.storage, &env, &pool)?;
+            Ok(Response::default())
+        },
+        |deps, env, msg: MockQueryMsg| -> StdResult<Binary> {
+            log_query(env.block.height, &msg);
+            check_query_failure()?;
+            if let Some(malformed) = malformed_response() {
+                return Ok(malformed);
+            }
+            check_query_kind_failure(msg.kind())?;
             match msg {
-                MockQueryMsg::Pair {} => Ok(to_binary(&mock_pair_info())?),
-                MockQueryMsg::Pool {} => Ok(to_binary(&mock_pool_info())?),
-                MockQueryMsg::TokenInfo {} => Ok(to_binary(&mock_token_info())?),
-                MockQueryMsg::Balance { address: _ } => Ok(to_binary(&mock_balance_info())?),
+                MockQueryMsg::Pair { asset_infos } => {
+                    let mut pair_info = mock_pair_info();
+                    if let Some(own_infos) = ASSET_INFOS.may_load(deps.storage)? {
+                        pair_info.asset_infos = own_infos;
+                    }
+                    if let Some(asset_infos) = asset_infos {
+                        if !asset_infos_match(&pair_info.asset_infos, &asset_infos) {
+                            return Err(MockError::PairMismatch {}.into());
+                        }
+                    }
+                    Ok(to_binary(&pair_info)?)
+                }
+                MockQueryMsg::Pool {} => {
+                    let lag = get_query_lag();
+                    let pool = if lag == 0 {
+                        load_pool(deps.storage, &env)?
+                    } else {
+                        lagged_pool(deps.storage, env.block.height, lag)?
+                    };
+                    Ok(to_binary(&PoolResponse::from(pool))?)
+                }
+                MockQueryMsg::Assets {} => {
+                    let [asset0, asset1] = POOL.load(deps.storage)?.assets;
+                    Ok(to_binary(&AssetsResponse {
+                        asset_infos: [asset0.info, asset1.info],
+                    })?)
+                }
+                MockQueryMsg::TokenInfo {} => {
+                    let mut token_info = TOKEN_INFO
+                        .may_load(deps.storage)?
+                        .unwrap_or_else(mock_token_info);
+                    let supply = TOTAL_SUPPLY
+                        .may_load(deps.storage)?
+                        .unwrap_or(token_info.total_supply);
+                    token_info.total_supply = to_rebased_amount(deps.storage, supply)?;
+                    Ok(to_binary(&token_info)?)
+                }
+                // Accounts nothing was moved to keep answering with the dummy balance
+                MockQueryMsg::Balance { address } => {
+                    let account = validate_addr(deps.api, &address)?;
+                    Ok(to_binary(&BalanceResponse {
+                        balance: balance_of(deps.storage, &account)?,
+                    })?)
+                }
+                MockQueryMsg::DetailedBalance { address } => {
+                    let account = validate_addr(deps.api, &address)?;
+                    let balance = balance_of(deps.storage, &account)?;
+                    // A lock bigger than the balance locks all of it
+                    let locked = get_locked(account.as_str()).min(balance);
+                    Ok(to_binary(&DetailedBalanceResponse {
+                        available: balance - locked,
+                        locked,
+                    })?)
+                }
+                MockQueryMsg::AllAccounts { start_after, limit } => {
+                    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+                    let start = start_after.map(Bound::exclusive);
+                    // Map ranges walk the keys in byte order, which is the order the accounts are listed in
+                    let accounts: StdResult<Vec<String>> = BALANCES
+                        .keys(deps.storage, start, None, Order::Ascending)
+                        .take(limit)
+                        .map(|key| Ok(String::from_utf8(key)?))
+                        .collect();
+                    Ok(to_binary(&AllAccountsResponse {
+                        accounts: accounts?,
+                    })?)
+                }
+                MockQueryMsg::Capabilities {} => Ok(to_binary(&mock_capabilities())?),
+                MockQueryMsg::Treasury {} => Ok(to_binary(&TreasuryResponse {
+                    treasury: get_treasury_addr(),
+                    fees: PROTOCOL_FEES.may_load(deps.storage)?.unwrap_or_default(),
+                })?),
+                MockQueryMsg::IsFrozen { addr } => {
+                    let account = validate_addr(deps.api, &addr)?;
+                    let frozen = FROZEN.may_load(deps.storage, &account)?.unwrap_or(false);
+                    Ok(to_binary(&IsFrozenResponse { frozen })?)
+                }
+                MockQueryMsg::Simulation { offer_asset } => {
+                    let pool = load_pool(deps.storage, &env)?;
+                    let split = pair_commission_split(deps.storage)?;
+                    Ok(to_binary(&simulate_swap_with(&pool, &offer_asset, split)?)?)
+                }
+                MockQueryMsg::PriceImpact { offer_asset } => {
+                    let pool = load_pool(deps.storage, &env)?;
+                    let split = pair_commission_split(deps.storage)?;
+                    Ok(to_binary(&PriceImpactResponse {
+                        price_impact: price_impact_with(&pool, &offer_asset, split)?,
+                    })?)
+                }
+                MockQueryMsg::ExchangeRate { offer_asset_info } => {
+                    let pool = load_pool(deps.storage, &env)?;
+                    let split = pair_commission_split(deps.storage)?;
+                    Ok(to_binary(&ExchangeRateResponse {
+                        exchange_rate: exchange_rate_with(&pool, &offer_asset_info, split)?,
+                    })?)
+                }
+                MockQueryMsg::SimulationBoth { offer_amount } => {
+                    let pool = load_pool(deps.storage, &env)?;
+                    let split = pair_commission_split(deps.storage)?;
+                    Ok(to_binary(&mock_simulation_both_with(
+                        &pool,
+                        offer_amount,
+                        split,
+                    )?)?)
+                }
+                MockQueryMsg::Config {} => {
+                    let split = pair_commission_split(deps.storage)?;
+                    Ok(to_binary(&ConfigResponse {
+                        commission_rate: split_rate(split),
+                        commission_split: split,
+                    })?)
+                }
+                MockQueryMsg::ProvideQuote { deposits } => {
+                    let pool = load_pool(deps.storage, &env)?;
+                    Ok(to_binary(&provide_quote(&pool, &deposits)?)?)
+                }
+                MockQueryMsg::BlockInfo {} => Ok(to_binary(&env.block)?),
+                MockQueryMsg::Trace {} => Ok(to_binary(&query_trace(deps.storage)?)?),
+                MockQueryMsg::Volume {} => Ok(to_binary(
+                    &VOLUME.may_load(deps.storage)?.unwrap_or_default(),
+                )?),
+                MockQueryMsg::KHistory { start_after, limit } => {
+                    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+                    let start = start_after.map(Bound::exclusive_int);
+                    let entries: StdResult<Vec<KEntry>> = K_HISTORY
+                        .range(deps.storage, start, None, Order::Ascending)
+                        .take(limit)
+                        .map(|item| Ok(item?.1))
+                        .collect();
+                    Ok(to_binary(&KHistoryResponse { entries: entries? })?)
+                }
+                MockQueryMsg::Version {} => Ok(to_binary(&query_version(deps.storage)?)?),
             }
         },
-    );
+    )
+    .with_sudo_empty(|deps, env, msg: MockSudoMsg| -> StdResult<Response> {
+        match msg {
+            MockSudoMsg::UpdateConfig { commission_rate } => {
+                if commission_rate > Decimal::one() {
+                    return Err(MockError::InvalidCommissionRate {}.into());
+                }
+                let total_bps = (Uint128::new(10_000) * commission_rate).u128() as u16;
+                let (_, protocol_bps) = pair_commission_split(deps.storage)?;
+                let protocol_bps = std::cmp::min(protocol_bps, total_bps);
+                COMMISSION.save(deps.storage, &(total_bps - protocol_bps, protocol_bps))?;
+                Ok(Response::new()
+                    .add_attribute("action", "update_config")
+                    .add_attribute("commission_rate", commission_rate.to_string()))
+            }
+            MockSudoMsg::LoadConfig { config } => {
+                load_config(deps, &env, config)?;
+                Ok(Response::new().add_attribute("action", "load_config"))
+            }
+        }
+    })
+    // Replies to the submessages the mock dispatches, the reply data is passed on as the mock's own data
+    .with_reply_empty(|_, _, reply: Reply| -> StdResult<Response> {
+        if reply.id != get_reply_id() {
+            return Err(MockError::UnknownReplyId { id: reply.id }.into());
+        }
+        let response = Response::new()
+            .add_attribute("action", "reply")
+            .add_attribute("reply_id", reply.id.to_string());
+        match reply.result {
+            ContractResult::Ok(result) => Ok(match result.data {
+                Some(data) => response.set_data(data),
+                None => response,
+            }),
+            // Only dispatched with ReplyOn::Error or Always, the failure is handled by recording it
+            ContractResult::Err(err) => Ok(response.add_attribute("error", err)),
+        }
+    })
+    .with_migrate_empty(|deps, _, msg: MigrateMsg| -> StdResult<Response> {
+        migrate_version(deps.storage, msg)
+    });
     Box::new(contract)
 }
 
-// 
+//
 // Mocked funcs to return data
-// 
+//
 
 // Return a BalanceResponse with dummy data
 pub fn mock_balance_info() -> BalanceResponse {
@@ -152,19 +1368,276 @@ pub fn mock_balance_info() -> BalanceResponse {
 
 // Acquire a write lock on the static value and then update it
 pub fn set_liq_token_addr(new_addr: String) -> String {
-    let mut addr = TOKEN_ADDR.write().unwrap();
+    let mut addr = TOKEN_ADDR.write();
     *addr = new_addr;
     return addr.to_string();
 }
 
 pub fn get_liq_token_addr() -> String {
-    return TOKEN_ADDR.read().unwrap().to_string();
+    return TOKEN_ADDR.read().to_string();
+}
+
+// Put every static of the terraswap mock back to its lazy_static default, see reset_mock_state.
+// Without this a liquidity token address set by one test is still there for the next test run in the same process.
+pub(crate) fn reset_state() {
+    set_liq_token_addr("string".to_string());
+    set_rounding(RoundingMode::Floor);
+    clear_fail_on();
+    set_commission_split(30, 0);
+    set_treasury_addr("treasury".to_string());
+    set_force_swap_overflow(false);
+    clear_scheduled_reserves();
+    set_reply_id(SEND_REPLY_ID);
+    set_send_reply_on(ReplyOn::Never);
+    set_reject_self_send(false);
+    set_minimum_liquidity(Uint128::new(1000));
+    set_pong_raw_bytes(false);
+    set_reject_zero_return(true);
+    clear_receive_response();
+    clear_transfer_hook();
+    clear_locked();
+    set_query_lag(0);
+    set_cw20_paused(false);
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_rounding(new_mode: RoundingMode) -> RoundingMode {
+    let mut mode = ROUNDING.write();
+    *mode = new_mode;
+    return *mode;
+}
+
+pub fn get_rounding() -> RoundingMode {
+    return *ROUNDING.read();
+}
+
+// Acquire a write lock on the static value and then update it.
+// Both parts are in bps of the swap output and together make up the commission, e.g. (25, 5) for 0.3% with a sixth to the protocol.
+pub fn set_commission_split(lp_bps: u16, protocol_bps: u16) -> (u16, u16) {
+    let mut split = COMMISSION_SPLIT.write();
+    *split = (lp_bps, protocol_bps);
+    return *split;
+}
+
+pub fn get_commission_split() -> (u16, u16) {
+    return *COMMISSION_SPLIT.read();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_treasury_addr(new_addr: String) -> String {
+    let mut addr = TREASURY_ADDR.write();
+    *addr = new_addr;
+    return addr.to_string();
+}
+
+pub fn get_treasury_addr() -> String {
+    return TREASURY_ADDR.read().to_string();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_reply_id(new_id: u64) -> u64 {
+    let mut id = REPLY_ID.write();
+    *id = new_id;
+    return *id;
+}
+
+pub fn get_reply_id() -> u64 {
+    return *REPLY_ID.read();
+}
+
+// Acquire a write lock on the static value and then update it.
+// Anything but ReplyOn::Never makes Send dispatch the receive hook as a submessage replied to with get_reply_id.
+pub fn set_send_reply_on(new_reply_on: ReplyOn) -> ReplyOn {
+    let mut reply_on = SEND_REPLY_ON.write();
+    *reply_on = new_reply_on;
+    return reply_on.clone();
+}
+
+pub fn get_send_reply_on() -> ReplyOn {
+    return SEND_REPLY_ON.read().clone();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_minimum_liquidity(new_minimum: Uint128) -> Uint128 {
+    let mut minimum = MINIMUM_LIQUIDITY.write();
+    *minimum = new_minimum;
+    return *minimum;
+}
+
+pub fn get_minimum_liquidity() -> Uint128 {
+    return *MINIMUM_LIQUIDITY.read();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_reject_self_send(reject: bool) -> bool {
+    let mut reject_self_send = REJECT_SELF_SEND.write();
+    *reject_self_send = reject;
+    return *reject_self_send;
+}
+
+pub fn get_reject_self_send() -> bool {
+    return *REJECT_SELF_SEND.read();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_reject_zero_return(reject: bool) -> bool {
+    let mut reject_zero_return = REJECT_ZERO_RETURN.write();
+    *reject_zero_return = reject;
+    return *reject_zero_return;
+}
+
+pub fn get_reject_zero_return() -> bool {
+    return *REJECT_ZERO_RETURN.read();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_pong_raw_bytes(raw: bool) -> bool {
+    let mut raw_bytes = PONG_RAW_BYTES.write();
+    *raw_bytes = raw;
+    return *raw_bytes;
+}
+
+pub fn get_pong_raw_bytes() -> bool {
+    return *PONG_RAW_BYTES.read();
+}
+
+// Make the Receive hook answer with these attributes and data instead of the default pong
+pub fn set_receive_response(attributes: Vec<Attribute>, data: Option<Binary>) {
+    let mut response = RECEIVE_RESPONSE.write();
+    *response = Some((attributes, data));
+}
+
+// Go back to answering the Receive hook with the pong
+pub fn clear_receive_response() {
+    *RECEIVE_RESPONSE.write() = None;
+}
+
+pub fn get_receive_response() -> Option<(Vec<Attribute>, Option<Binary>)> {
+    return RECEIVE_RESPONSE.read().clone();
+}
+
+// Acquire a write lock on the static value and then update it
+pub fn set_transfer_hook(new_hook: String) -> String {
+    let mut hook = TRANSFER_HOOK.write();
+    *hook = Some(new_hook.clone());
+    return new_hook;
+}
+
+// Stop notifying a hook receiver of transfers
+pub fn clear_transfer_hook() {
+    *TRANSFER_HOOK.write() = None;
+}
+
+pub fn get_transfer_hook() -> Option<String> {
+    return TRANSFER_HOOK.read().clone();
+}
+
+// Acquire a write lock on the static value and then update it, a zero amount unlocks the account
+pub fn set_locked(addr: String, amount: Uint128) -> Uint128 {
+    let mut locked = LOCKED.write();
+    if amount.is_zero() {
+        locked.remove(&addr);
+    } else {
+        locked.insert(addr, amount);
+    }
+    return amount;
+}
+
+pub fn get_locked(addr: &str) -> Uint128 {
+    return LOCKED.read().get(addr).copied().unwrap_or_default();
+}
+
+// Unlock every account
+pub fn clear_locked() {
+    LOCKED.write().clear();
+}
+
+// Make the Pool query answer with the pool as it was `blocks` blocks ago, like an oracle that reports state late.
+// Only the Pool query lags, swaps and simulations keep using the live reserves.
+pub fn set_query_lag(blocks: u64) -> u64 {
+    let mut lag = QUERY_LAG.write();
+    *lag = blocks;
+    return *lag;
+}
+
+pub fn get_query_lag() -> u64 {
+    return *QUERY_LAG.read();
+}
+
+// Acquire a write lock on the static value and then update it.
+// While paused Transfer, TransferFrom and Send error, the queries keep answering.
+pub fn set_cw20_paused(paused: bool) -> bool {
+    let mut cw20_paused = CW20_PAUSED.write();
+    *cw20_paused = paused;
+    return *cw20_paused;
+}
+
+pub fn get_cw20_paused() -> bool {
+    return *CW20_PAUSED.read();
+}
+
+// Set the reserves of the pair at `contract_addr` to these two assets once the block height reaches `at_height`,
+// before that the pool keeps answering with its current reserves. Other pairs aren't affected.
+// Mimics reserves that only update on a later block, e.g. a delayed TWAP.
+pub fn schedule_reserves(contract_addr: String, at_height: u64, asset0: Asset, asset1: Asset) {
+    let mut seq = SCHEDULE_SEQ.write();
+    *seq += 1;
+    let mut scheduled = SCHEDULED_RESERVES.write();
+    scheduled.insert(contract_addr, (*seq, at_height, [asset0, asset1]));
+}
+
+// Drop the pending reserve changes of every pair that haven't been applied yet
+pub fn clear_scheduled_reserves() {
+    let mut scheduled = SCHEDULED_RESERVES.write();
+    scheduled.clear();
+}
+
+// Make the swap math return an overflow error whatever the reserves, so Swap, Simulation and anything routed
+// through them hit their overflow branch without contrived inputs
+pub fn set_force_swap_overflow(force: bool) -> bool {
+    let mut force_overflow = FORCE_SWAP_OVERFLOW.write();
+    *force_overflow = force;
+    return *force_overflow;
+}
+
+pub fn get_force_swap_overflow() -> bool {
+    return *FORCE_SWAP_OVERFLOW.read();
+}
+
+// Make every execute of this kind fail, e.g. only Swap, while the other messages keep working
+pub fn set_fail_on_execute(kind: MockExecuteMsgKind) -> bool {
+    return FAIL_ON_EXECUTE.write().insert(kind);
+}
+
+// Make every query of this kind fail, e.g. only Pool, while the other queries keep working
+pub fn set_fail_on_query(kind: MockQueryMsgKind) -> bool {
+    return FAIL_ON_QUERY.write().insert(kind);
+}
+
+// Let every kind of execute and query succeed again
+pub fn clear_fail_on() {
+    FAIL_ON_EXECUTE.write().clear();
+    FAIL_ON_QUERY.write().clear();
+}
+
+fn check_execute_kind_failure(kind: MockExecuteMsgKind) -> StdResult<()> {
+    if FAIL_ON_EXECUTE.read().contains(&kind) {
+        return Err(mock_failure(&format!("Mock execute failure on {:?}", kind)));
+    }
+    Ok(())
+}
+
+fn check_query_kind_failure(kind: MockQueryMsgKind) -> StdResult<()> {
+    if FAIL_ON_QUERY.read().contains(&kind) {
+        return Err(mock_failure(&format!("Mock query failure on {:?}", kind)));
+    }
+    Ok(())
 }
 
 // Return a PairResponse with dummy data
 pub fn mock_pair_info() -> PairResponse {
-    let resp: PairResponse = PairResponse {
-        asset_infos: [
+    return mock_pair_info_with(
+        [
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
@@ -172,15 +1645,33 @@ pub fn mock_pair_info() -> PairResponse {
                 denom: "uusd".to_string(),
             },
         ],
-        contract_addr: "pair0000".to_string(),
-        liquidity_token: get_liq_token_addr(),
+        "pair0000".to_string(),
+        get_liq_token_addr(),
+    );
+}
+
+// Return a PairResponse with the given fields, doesn't read or touch the lazy statics
+pub fn mock_pair_info_with(
+    asset_infos: [AssetInfo; 2],
+    contract_addr: String,
+    liquidity_token: String,
+) -> PairResponse {
+    let resp: PairResponse = PairResponse {
+        asset_infos,
+        contract_addr,
+        liquidity_token,
     };
     return resp;
 }
 
-// Return a PoolResponse with dummy data
-pub fn mock_pool_info() {
-    to_binary(&PoolResponse {
+// Check two sets of asset infos describe the same pair, in either order
+pub fn asset_infos_match(a: &[AssetInfo; 2], b: &[AssetInfo; 2]) -> bool {
+    (a[0] == b[0] && a[1] == b[1]) || (a[0] == b[1] && a[1] == b[0])
+}
+
+// Return a PoolResponse with dummy data, also used as the starting reserves of the pool
+pub fn mock_pool_info() -> PoolResponse {
+    let resp: PoolResponse = PoolResponse {
         assets: [
             Asset {
                 amount: Uint128::from(10000u128),
@@ -196,13 +1687,484 @@ pub fn mock_pool_info() {
             },
         ],
         total_share: Uint128::from(1000u128),
+    };
+    return resp;
+}
+
+//
+// Liquidity math used by the pool handlers
+//
+
+// Reorder the given deposits to match the order of the pool assets, erroring if they aren't the pool's assets
+fn deposit_amounts(pool: &PoolState, deposits: &[Asset; 2]) -> StdResult<[Uint128; 2]> {
+    let mut amounts = [Uint128::zero(); 2];
+    for (i, pool_asset) in pool.assets.iter().enumerate() {
+        amounts[i] = deposits
+            .iter()
+            .find(|deposit| deposit.info == pool_asset.info)
+            .map(|deposit| deposit.amount)
+            .ok_or(MockError::AssetMismatch {})?;
+    }
+    Ok(amounts)
+}
+
+// Integer square root, used for the first deposit into an empty pool.
+// Works on Uint256 so the product of two deposits can't overflow.
+fn isqrt(value: Uint256) -> Uint256 {
+    let one = Uint256::from(1u64);
+    let two = Uint256::from(2u64);
+    if value < two {
+        return value;
+    }
+    let mut x = value;
+    let mut y = (x + one) / two;
+    while y < x {
+        x = y;
+        y = (x + value / x) / two;
+    }
+    x
+}
+
+// The LP shares minted to the provider for depositing `deposit0` and `deposit1` into a pool with the given reserves
+// and supply. The first deposit mints sqrt(deposit0 * deposit1) less the locked minimum liquidity, later ones mint
+// pro-rata to the smaller side like Terraswap does.
+// This is the math ProvideLiquidity uses, exposed so tests can work out the expected mint on their own.
+pub fn expected_lp_mint(
+    reserve0: Uint128,
+    reserve1: Uint128,
+    total_share: Uint128,
+    deposit0: Uint128,
+    deposit1: Uint128,
+) -> Uint128 {
+    if total_share.is_zero() {
+        let minted: Uint128 = isqrt(Uint256::from(deposit0) * Uint256::from(deposit1)).into();
+        return minted.saturating_sub(get_minimum_liquidity());
+    }
+    std::cmp::min(
+        deposit0.multiply_ratio(total_share, reserve0),
+        deposit1.multiply_ratio(total_share, reserve1),
+    )
+}
+
+// Add the deposits to the pool reserves and return the shares minted for them, see expected_lp_mint
+pub fn provide_liquidity(pool: &mut PoolState, deposits: &[Asset; 2]) -> StdResult<Share> {
+    let amounts = deposit_amounts(pool, deposits)?;
+    let share = Share(expected_lp_mint(
+        pool.assets[0].amount,
+        pool.assets[1].amount,
+        pool.total_share.into(),
+        amounts[0],
+        amounts[1],
+    ));
+    if share.is_zero() {
+        return Err(MockError::DepositTooSmall {}.into());
+    }
+    // The locked minimum liquidity counts towards the supply but belongs to nobody
+    if pool.total_share.is_zero() {
+        pool.total_share = Share(get_minimum_liquidity());
+    }
+    for (pool_asset, amount) in pool.assets.iter_mut().zip(amounts.iter()) {
+        pool_asset.amount = pool_asset.amount.checked_add(*amount)?;
+    }
+    pool.total_share = pool.total_share.checked_add(share)?;
+    Ok(share)
+}
+
+// Preview a provide without touching the pool. The shares are what provide_liquidity would mint and the refund is
+// the excess of the larger side that the minted shares don't account for, which real pairs send back.
+pub fn provide_quote(pool: &PoolState, deposits: &[Asset; 2]) -> StdResult<ProvideQuoteResponse> {
+    let amounts = deposit_amounts(pool, deposits)?;
+    let total_share: Uint128 = pool.total_share.into();
+    let share = expected_lp_mint(
+        pool.assets[0].amount,
+        pool.assets[1].amount,
+        total_share,
+        amounts[0],
+        amounts[1],
+    );
+    let mut refund_assets = [pool.assets[0].clone(), pool.assets[1].clone()];
+    for (i, refund_asset) in refund_assets.iter_mut().enumerate() {
+        // The first deposit sets the ratio so all of it is used
+        let used = if total_share.is_zero() {
+            amounts[i]
+        } else {
+            pool.assets[i].amount.multiply_ratio(share, total_share)
+        };
+        refund_asset.amount = amounts[i].saturating_sub(used);
+    }
+    Ok(ProvideQuoteResponse {
+        share,
+        refund_assets,
+    })
+}
+
+// Burn the given shares and return the pro-rata part of each reserve they were worth
+pub fn withdraw_liquidity(pool: &mut PoolState, share: Share) -> StdResult<[Asset; 2]> {
+    let mut refund_assets = pool.assets.clone();
+    for (pool_asset, refund_asset) in pool.assets.iter_mut().zip(refund_assets.iter_mut()) {
+        refund_asset.amount = share.portion_of(pool_asset.amount, pool.total_share)?;
+        pool_asset.amount = pool_asset.amount.checked_sub(refund_asset.amount)?;
+    }
+    pool.total_share = pool.total_share.checked_sub(share)?;
+    Ok(refund_assets)
+}
+
+//
+// Swap math used by the pool handlers
+//
+
+// Commission taken from every swap, the LP and protocol parts of set_commission_split together (0.3% by default).
+// A pair whose commission was changed by the UpdateConfig sudo reports its own with the Config query.
+pub fn commission_rate() -> Decimal {
+    split_rate(get_commission_split())
+}
+
+// The commission rate of an (lp_bps, protocol_bps) split
+fn split_rate((lp_bps, protocol_bps): (u16, u16)) -> Decimal {
+    Decimal::from_ratio(lp_bps as u128 + protocol_bps as u128, 10_000u128)
+}
+
+// Check the whole fixture before writing any of it, so a bad one leaves the pair as it was. The amounts are taken as
+// they are, any earlier rebase is dropped along with the balances it applied to.
+fn load_config(deps: DepsMut, env: &Env, config: MockConfig) -> StdResult<()> {
+    let invalid = |reason: String| -> StdError { MockError::InvalidMockConfig { reason }.into() };
+    let (lp_bps, protocol_bps) = config.commission_split;
+    if lp_bps as u32 + protocol_bps as u32 > 10_000 {
+        return Err(invalid(format!(
+            "commission_split ({}, {}) is above 10000 bps",
+            lp_bps, protocol_bps
+        )));
+    }
+    if split_rate(config.commission_split) != config.commission_rate {
+        return Err(invalid(format!(
+            "commission_rate {} doesn't match commission_split ({}, {})",
+            config.commission_rate, lp_bps, protocol_bps
+        )));
+    }
+    let mut balances: Vec<(Addr, Uint128)> = vec![];
+    for (account, balance) in config.balances {
+        let account = validate_addr(deps.api, &account)?;
+        if balances.iter().any(|(seen, _)| *seen == account) {
+            return Err(invalid(format!("{} has more than one balance", account)));
+        }
+        balances.push((account, balance));
+    }
+
+    let accounts: Vec<Addr> = BALANCES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| Ok(Addr::unchecked(String::from_utf8(key)?)))
+        .collect::<StdResult<_>>()?;
+    for account in accounts {
+        BALANCES.remove(deps.storage, &account);
+    }
+    for (account, balance) in balances {
+        BALANCES.save(deps.storage, &account, &balance)?;
+    }
+    REBASE_FACTOR.remove(deps.storage);
+    // What the pair recorded about its swaps so far doesn't belong to the loaded pool
+    let seq = K_SEQ.may_load(deps.storage)?.unwrap_or_default();
+    for seq in seq.saturating_sub(MAX_K_HISTORY) + 1..=seq {
+        K_HISTORY.remove(deps.storage, seq.into());
+    }
+    K_SEQ.remove(deps.storage);
+    VOLUME.remove(deps.storage);
+    PROTOCOL_FEES.remove(deps.storage);
+    // A reserve change still pending for this pair would overwrite the loaded pool, it counts as applied
+    if let Some((seq, _, _)) = SCHEDULED_RESERVES.read().get(env.contract.address.as_str()) {
+        SCHEDULE_APPLIED.save(deps.storage, seq)?;
+    }
+    TOTAL_SUPPLY.save(deps.storage, &config.token_info.total_supply)?;
+    TOKEN_INFO.save(deps.storage, &config.token_info)?;
+    COMMISSION.save(deps.storage, &config.commission_split)?;
+    let [asset0, asset1] = config.pool.assets;
+    ASSET_INFOS.save(deps.storage, &[asset0.info.clone(), asset1.info.clone()])?;
+    save_pool(
+        deps.storage,
+        env,
+        &PoolState {
+            assets: [asset0, asset1],
+            total_share: config.pool.total_share.into(),
+        },
+    )
+}
+
+// The commission split of the pair whose storage this is, see COMMISSION
+fn pair_commission_split(storage: &dyn Storage) -> StdResult<(u16, u16)> {
+    Ok(COMMISSION
+        .may_load(storage)?
+        .unwrap_or_else(get_commission_split))
+}
+
+// Constant product swap math, matching Terraswap's compute_swap:
+// return_amount = (ask_pool - offer_pool * ask_pool / (offer_pool + offer_amount)) - commission
+pub fn compute_swap(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    commission_rate: Decimal,
+) -> StdResult<SimulationResponse> {
+    if get_force_swap_overflow() {
+        return Err(StdError::overflow(OverflowError::new(
+            OverflowOperation::Add,
+            offer_pool,
+            offer_amount,
+        )));
+    }
+    if offer_pool.is_zero() || ask_pool.is_zero() {
+        return Err(MockError::PoolEmpty {}.into());
+    }
+    let offer_pool_256 = Uint256::from(offer_pool);
+    let ask_pool_256 = Uint256::from(ask_pool);
+    let cp = offer_pool_256 * ask_pool_256;
+    let new_offer_pool = offer_pool_256 + Uint256::from(offer_amount);
+    // The output is rounded by rounding the remaining ask pool the other way, see set_rounding
+    let mut new_ask_pool = cp / new_offer_pool;
+    let remainder = cp - new_ask_pool * new_offer_pool;
+    let round_ask_pool_up = match get_rounding() {
+        RoundingMode::Floor => remainder != Uint256::zero(),
+        RoundingMode::Ceil => false,
+        RoundingMode::Nearest => remainder + remainder > new_offer_pool,
+    };
+    if round_ask_pool_up {
+        new_ask_pool = new_ask_pool + Uint256::from(1u64);
+    }
+    // Paying out the whole ask pool would leave it empty
+    if new_ask_pool == Uint256::zero() {
+        return Err(MockError::InsufficientLiquidity {}.into());
+    }
+    let return_amount: Uint128 = (ask_pool_256 - new_ask_pool).into();
+    let spread_amount = offer_amount
+        .multiply_ratio(ask_pool, offer_pool)
+        .saturating_sub(return_amount);
+    let commission_amount = return_amount * commission_rate;
+    Ok(SimulationResponse {
+        return_amount: return_amount.checked_sub(commission_amount)?,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+// Index of the pool asset matching the offered asset, so the other one is what's asked for.
+// An offer of anything but the pair's two assets is rejected rather than swapped against either side.
+fn offer_index(pool: &PoolState, offer_info: &AssetInfo) -> StdResult<usize> {
+    pool.assets
+        .iter()
+        .position(|pool_asset| pool_asset.info == *offer_info)
+        .ok_or_else(|| {
+            MockError::OfferAssetMismatch {
+                offer: offer_info.to_string(),
+                asset0: pool.assets[0].info.to_string(),
+                asset1: pool.assets[1].info.to_string(),
+            }
+            .into()
+        })
+}
+
+// Simulate swapping the offered asset against the pool without changing it
+pub fn simulate_swap(pool: &PoolState, offer_asset: &Asset) -> StdResult<SimulationResponse> {
+    simulate_swap_with(pool, offer_asset, get_commission_split())
+}
+
+fn simulate_swap_with(
+    pool: &PoolState,
+    offer_asset: &Asset,
+    split: (u16, u16),
+) -> StdResult<SimulationResponse> {
+    let offer = offer_index(pool, &offer_asset.info)?;
+    compute_swap(
+        pool.assets[offer].amount,
+        pool.assets[1 - offer].amount,
+        offer_asset.amount,
+        split_rate(split),
+    )
+}
+
+// Swap the offered asset against the pool, moving the reserves. The LP part of the commission stays in the pool
+// like in Terraswap, the protocol part leaves it and is returned alongside the swap result.
+pub fn apply_swap(
+    pool: &mut PoolState,
+    offer_asset: &Asset,
+) -> StdResult<(SimulationResponse, Asset)> {
+    swap_reserves(
+        pool,
+        offer_asset,
+        get_commission_split(),
+        get_reject_zero_return(),
+    )
+}
+
+// apply_swap with the commission split and the zero return check given by the caller, so the pairs can use their own
+// commission and what-if math can still run on dust offers
+fn swap_reserves(
+    pool: &mut PoolState,
+    offer_asset: &Asset,
+    split: (u16, u16),
+    reject_zero_return: bool,
+) -> StdResult<(SimulationResponse, Asset)> {
+    let offer = offer_index(pool, &offer_asset.info)?;
+    let k_before = pool_k(pool);
+    let swap = simulate_swap_with(pool, offer_asset, split)?;
+    // Dust offers would take the offer in for nothing, see set_reject_zero_return
+    if swap.return_amount.is_zero() && reject_zero_return {
+        return Err(MockError::ZeroReturnAmount {}.into());
+    }
+    let (lp_bps, protocol_bps) = split;
+    let total_bps = lp_bps as u128 + protocol_bps as u128;
+    let protocol_fee = Asset {
+        info: pool.assets[1 - offer].info.clone(),
+        amount: if total_bps == 0 {
+            Uint128::zero()
+        } else {
+            swap.commission_amount
+                .multiply_ratio(protocol_bps as u128, total_bps)
+        },
+    };
+    let paid_out = swap.return_amount.checked_add(protocol_fee.amount)?;
+    if paid_out >= pool.assets[1 - offer].amount {
+        return Err(MockError::InsufficientLiquidity {}.into());
+    }
+    pool.assets[offer].amount = pool.assets[offer].amount.checked_add(offer_asset.amount)?;
+    pool.assets[1 - offer].amount = pool.assets[1 - offer].amount.checked_sub(paid_out)?;
+    assert_k_not_decreased(pool, offer, k_before)?;
+    Ok((swap, protocol_fee))
+}
+
+// The constant product of the reserves, in 256 bits so large reserves can't overflow it
+pub fn pool_k(pool: &PoolState) -> Uint256 {
+    Uint256::from(pool.assets[0].amount) * Uint256::from(pool.assets[1].amount)
+}
+
+// Catch regressions in the swap math: the commission stays in the pool so a swap can only grow k. Rounding the
+// output up (RoundingMode::Ceil or Nearest) can pay out one unit of the ask asset more than the curve allows,
+// which takes at most the new offer reserve off k, so that much is tolerated outside of Floor.
+fn assert_k_not_decreased(pool: &PoolState, offer: usize, k_before: Uint256) -> StdResult<()> {
+    let k_after = pool_k(pool);
+    let slack = match get_rounding() {
+        RoundingMode::Floor => Uint256::zero(),
+        RoundingMode::Ceil | RoundingMode::Nearest => Uint256::from(pool.assets[offer].amount),
+    };
+    if k_after + slack < k_before {
+        return Err(MockError::KDecreased {
+            before: k_before.to_string(),
+            after: k_after.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+// The relative move of the pool's marginal price (ask reserve / offer reserve) the swap would cause,
+// from the reserves before the swap to the reserves after it. A dust offer has an impact even when the swap itself
+// would be rejected for returning nothing.
+pub fn price_impact(pool: &PoolState, offer_asset: &Asset) -> StdResult<Decimal> {
+    price_impact_with(pool, offer_asset, get_commission_split())
+}
+
+fn price_impact_with(
+    pool: &PoolState,
+    offer_asset: &Asset,
+    split: (u16, u16),
+) -> StdResult<Decimal> {
+    let offer = offer_index(pool, &offer_asset.info)?;
+    let mut after = pool.clone();
+    swap_reserves(&mut after, offer_asset, split, false)?;
+    // post / pre = (new_ask / new_offer) / (ask / offer)
+    let scaled_post = after.assets[1 - offer]
+        .amount
+        .multiply_ratio(pool.assets[offer].amount, after.assets[offer].amount);
+    Ok(Decimal::one() - Decimal::from_ratio(scaled_post, pool.assets[1 - offer].amount))
+}
+
+// How many times over exchange_rate scales the reserves, so its one unit offer is a 1e-18 fraction of the offer
+// reserve and the spread it causes is below the precision of a Decimal
+const EXCHANGE_RATE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+// The marginal rate the pool pays for the offered asset, commission included. Runs the constant product swap math
+// on an infinitesimal offer: one unit against the reserves scaled by EXCHANGE_RATE_SCALE, kept as an exact ratio
+// on Uint256 instead of rounding the return to whole units. The commission then comes off like on a real swap,
+// unlike the bare reserve ratio ask / offer which ignores it.
+pub fn exchange_rate(pool: &PoolState, offer_asset_info: &AssetInfo) -> StdResult<Decimal> {
+    exchange_rate_with(pool, offer_asset_info, get_commission_split())
+}
+
+fn exchange_rate_with(
+    pool: &PoolState,
+    offer_asset_info: &AssetInfo,
+    split: (u16, u16),
+) -> StdResult<Decimal> {
+    let offer = offer_index(pool, offer_asset_info)?;
+    let offer_pool = pool.assets[offer].amount;
+    let ask_pool = pool.assets[1 - offer].amount;
+    if offer_pool.is_zero() || ask_pool.is_zero() {
+        return Err(MockError::PoolEmpty {}.into());
+    }
+    // The return of a one unit offer is ask_pool * scale / (offer_pool * scale + 1)
+    let scale = Uint256::from(EXCHANGE_RATE_SCALE);
+    let return_per_unit = Decimal256::from_ratio(
+        Uint256::from(ask_pool) * scale,
+        Uint256::from(offer_pool) * scale + Uint256::from(1u64),
+    );
+    let after_commission = Decimal256::one() - Decimal256::from(split_rate(split));
+    Ok(Decimal::from(return_per_unit * after_commission))
+}
+
+// Simulate offering `offer_amount` of each side of the pool. The forward leg offers the uusd side
+// (or the first asset if the pool has no uusd) and the reverse leg offers the other side.
+pub fn mock_simulation_both(
+    pool: &PoolState,
+    offer_amount: Uint128,
+) -> StdResult<SimulationBothResponse> {
+    mock_simulation_both_with(pool, offer_amount, get_commission_split())
+}
+
+fn mock_simulation_both_with(
+    pool: &PoolState,
+    offer_amount: Uint128,
+    split: (u16, u16),
+) -> StdResult<SimulationBothResponse> {
+    let forward = pool
+        .assets
+        .iter()
+        .position(|pool_asset| {
+            pool_asset.info
+                == AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                }
+        })
+        .unwrap_or(0);
+    let offer = |index: usize| Asset {
+        info: pool.assets[index].info.clone(),
+        amount: offer_amount,
+    };
+    Ok(SimulationBothResponse {
+        forward: simulate_swap_with(pool, &offer(forward), split)?,
+        reverse: simulate_swap_with(pool, &offer(1 - forward), split)?,
     })
-    .unwrap_or_default();
+}
+
+//
+// Conversions between Terraswap assets and native coins, for funding the native legs of messages
+//
+
+// The Coin for a native asset, None for a cw20 asset which can't be sent as funds
+pub fn asset_to_coin(asset: &Asset) -> Option<Coin> {
+    match &asset.info {
+        AssetInfo::NativeToken { denom } => Some(Coin {
+            denom: denom.to_string(),
+            amount: asset.amount,
+        }),
+        AssetInfo::Token { .. } => None,
+    }
+}
+
+// The Coins for the native assets in the list, cw20 assets are skipped
+pub fn coins_from_assets(assets: &[Asset]) -> Vec<Coin> {
+    assets.iter().filter_map(asset_to_coin).collect()
 }
 
 // Return a TokenInfoResponse with dummy data
 pub fn mock_token_info() -> TokenInfoResponse {
-    // TODO: Maybe make these changable via lazy statics 
+    // TODO: Maybe make these changable via lazy statics
     let resp: TokenInfoResponse = TokenInfoResponse {
         name: "MyToken".to_string(),
         symbol: "TOKEN".to_string(),
@@ -211,3 +2173,149 @@ pub fn mock_token_info() -> TokenInfoResponse {
     };
     return resp;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_converts_to_and_from_uint128() {
+        let share = Share::from(Uint128::new(42));
+        assert_eq!(share, Share(Uint128::new(42)));
+        assert_eq!(Uint128::from(share), Uint128::new(42));
+        let into: Uint128 = share.into();
+        assert_eq!(into, Uint128::new(42));
+        assert!(Share::zero().is_zero());
+        assert_eq!(Share::default(), Share::zero());
+    }
+
+    #[test]
+    fn share_math_is_checked() {
+        let one = Share(Uint128::new(1));
+        let max = Share(Uint128::new(u128::MAX));
+        assert_eq!(one.checked_add(one).unwrap(), Share(Uint128::new(2)));
+        assert_eq!(
+            max.checked_sub(one).unwrap(),
+            Share(Uint128::new(u128::MAX - 1))
+        );
+        assert!(max.checked_add(one).is_err());
+        assert!(Share::zero().checked_sub(one).is_err());
+    }
+
+    #[test]
+    fn share_portion_of() {
+        let share = Share(Uint128::new(250));
+        let total = Share(Uint128::new(1000));
+        assert_eq!(
+            share.portion_of(Uint128::new(10_000), total).unwrap(),
+            Uint128::new(2_500)
+        );
+        assert_eq!(
+            share.portion_of(Uint128::new(10_000), Share::zero()),
+            Err(MockError::PoolHasNoShares {}.into())
+        );
+    }
+
+    #[test]
+    fn share_serializes_as_a_plain_amount() {
+        let share = Share(Uint128::new(7));
+        assert_eq!(
+            to_binary(&share).unwrap(),
+            to_binary(&Uint128::new(7)).unwrap()
+        );
+        assert_eq!(
+            from_binary::<Share>(&to_binary(&"7").unwrap()).unwrap(),
+            share
+        );
+    }
+
+    #[test]
+    fn pair_info_with_the_given_fields() {
+        let asset_infos = [
+            AssetInfo::Token {
+                contract_addr: "token_a".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "token_b".to_string(),
+            },
+        ];
+        let pair_info =
+            mock_pair_info_with(asset_infos.clone(), "pair".to_string(), "lp".to_string());
+        assert_eq!(pair_info.asset_infos, asset_infos);
+        assert_eq!(pair_info.contract_addr, "pair");
+        assert_eq!(pair_info.liquidity_token, "lp");
+    }
+
+    #[test]
+    fn native_assets_convert_to_coins() {
+        let uusd = Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::new(100),
+        };
+        let cw20 = Asset {
+            info: AssetInfo::Token {
+                contract_addr: "token".to_string(),
+            },
+            amount: Uint128::new(50),
+        };
+        assert_eq!(asset_to_coin(&uusd), Some(Coin::new(100, "uusd")));
+        assert_eq!(asset_to_coin(&cw20), None);
+        // The cw20 is skipped and the order of the natives kept
+        let uluna = Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::zero(),
+        };
+        assert_eq!(
+            coins_from_assets(&[uusd, cw20, uluna]),
+            vec![Coin::new(100, "uusd"), Coin::new(0, "uluna")]
+        );
+        assert_eq!(coins_from_assets(&[]), vec![]);
+    }
+
+    #[test]
+    fn expected_lp_mint_first_deposit_is_the_root_less_the_minimum() {
+        let mint = |deposit0: u128, deposit1: u128| {
+            expected_lp_mint(
+                Uint128::zero(),
+                Uint128::zero(),
+                Uint128::zero(),
+                Uint128::new(deposit0),
+                Uint128::new(deposit1),
+            )
+            .u128()
+        };
+        // sqrt(4000 * 9000) = 6000, less the default 1000 locked
+        assert_eq!(mint(4_000, 9_000), 5_000);
+        // The root rounds down, sqrt(2_000_000 * 3_000_000) is 2449489.74
+        assert_eq!(mint(2_000_000, 3_000_000), 2_448_489);
+        // Too little to cover the locked minimum mints nothing
+        assert_eq!(mint(30, 30), 0);
+        assert_eq!(mint(1_000, 1_000), 0);
+        assert_eq!(isqrt(Uint256::from(6u64)), Uint256::from(2u64));
+    }
+
+    #[test]
+    fn expected_lp_mint_later_deposits_are_pro_rata_to_the_smaller_side() {
+        let mint = |deposit0: u128, deposit1: u128| {
+            expected_lp_mint(
+                Uint128::new(10_000),
+                Uint128::new(20_000),
+                Uint128::new(14_142),
+                Uint128::new(deposit0),
+                Uint128::new(deposit1),
+            )
+            .u128()
+        };
+        // Balanced, a tenth of the reserves mints a tenth of the supply
+        assert_eq!(mint(1_000, 2_000), 1_414);
+        // The extra asset1 earns nothing
+        assert_eq!(mint(1_000, 3_000), 1_414);
+        // The smaller side decides, rounding down
+        assert_eq!(mint(3_000, 1_000), 707);
+        assert_eq!(mint(0, 2_000), 0);
+    }
+}
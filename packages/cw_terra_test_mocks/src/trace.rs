@@ -0,0 +1,50 @@
+use cosmwasm_std::{MessageInfo, StdResult, Storage};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+// The response that should be returned by the Trace query, oldest line first
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TraceResponse {
+    pub lines: Vec<String>,
+}
+
+// Kept in the mock's own storage so every mock instance has its own timeline
+pub const TRACE: Item<Vec<String>> = Item::new("mock_trace");
+
+// Record a line for an execute hitting the mock, the message's Debug output carries the action and amounts.
+// Does nothing unless the trace feature is on, so normal runs don't pay for the extra storage writes.
+pub fn record_trace<M: Debug>(
+    storage: &mut dyn Storage,
+    height: u64,
+    info: &MessageInfo,
+    msg: &M,
+) -> StdResult<()> {
+    if !cfg!(feature = "trace") {
+        return Ok(());
+    }
+    let line = format!(
+        "height {}: {:?} from {} with funds {:?}",
+        height, msg, info.sender, info.funds
+    );
+    #[cfg(feature = "trace")]
+    log::debug!(target: "cw_terra_test_mocks", "execute {}", line);
+    let mut lines = TRACE.may_load(storage)?.unwrap_or_default();
+    lines.push(line);
+    TRACE.save(storage, &lines)
+}
+
+// Log a query hitting the mock, queries can't write to storage so they only show up in the log
+pub fn log_query<M: Debug>(height: u64, msg: &M) {
+    #[cfg(feature = "trace")]
+    log::debug!(target: "cw_terra_test_mocks", "query height {}: {:?}", height, msg);
+    #[cfg(not(feature = "trace"))]
+    let _ = (height, msg);
+}
+
+// The lines recorded so far, always empty without the trace feature
+pub fn query_trace(storage: &dyn Storage) -> StdResult<TraceResponse> {
+    let lines = TRACE.may_load(storage)?.unwrap_or_default();
+    Ok(TraceResponse { lines })
+}
@@ -0,0 +1,72 @@
+use cosmwasm_std::{Addr, Api, StdResult};
+use lazy_static::lazy_static;
+
+use crate::errors::MockError;
+use crate::lock::Lock;
+
+// The characters bech32 uses for the data part of an address
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+// The mock api accepts pretty much any string as an address, this lazy static allows you the dev to make the mocks pickier
+lazy_static! {
+    static ref STRICT_ADDRESS_VALIDATION: Lock<bool> = Lock::new(false);
+}
+
+// Make the mocks reject addresses that don't look like bech32 (e.g. terra1...), so contracts forwarding malformed
+// addresses get caught. Off by default since multi-test apps usually use plain names like "owner".
+pub fn set_strict_address_validation(strict: bool) -> bool {
+    let mut strict_validation = STRICT_ADDRESS_VALIDATION.write();
+    *strict_validation = strict;
+    return *strict_validation;
+}
+
+pub fn get_strict_address_validation() -> bool {
+    return *STRICT_ADDRESS_VALIDATION.read();
+}
+
+// Put the statics back to the defaults above, see reset_mock_state
+pub(crate) fn reset_state() {
+    set_strict_address_validation(false);
+}
+
+// A lowercase human readable part, the "1" separator and at least six bech32 data characters. The checksum isn't verified.
+fn is_bech32_like(addr: &str) -> bool {
+    let (hrp, data) = match addr.rfind('1') {
+        Some(separator) => (&addr[..separator], &addr[separator + 1..]),
+        None => return false,
+    };
+    !hrp.is_empty()
+        && hrp.chars().all(|c| c.is_ascii_lowercase())
+        && data.len() >= 6
+        && data.chars().all(|c| BECH32_CHARSET.contains(c))
+}
+
+// Used by the mock handlers instead of calling addr_validate directly so strict mode applies everywhere
+pub fn validate_addr(api: &dyn Api, addr: &str) -> StdResult<Addr> {
+    if get_strict_address_validation() && !is_bech32_like(addr) {
+        return Err(MockError::InvalidAddress {
+            addr: addr.to_string(),
+        }
+        .into());
+    }
+    api.addr_validate(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bech32_like_addresses() {
+        assert!(is_bech32_like(
+            "terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8"
+        ));
+        assert!(is_bech32_like("terra1qpzry9"));
+        // Too short, no separator, uppercase or outside the charset
+        assert!(!is_bech32_like("terra1qpzry"));
+        assert!(!is_bech32_like("owner"));
+        assert!(!is_bech32_like("Terra1qpzry9"));
+        assert!(!is_bech32_like("terra1bio"));
+        assert!(!is_bech32_like("1qpzry9x8"));
+    }
+}
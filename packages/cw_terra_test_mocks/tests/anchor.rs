@@ -0,0 +1,65 @@
+#![cfg(all(feature = "anchor", feature = "terraswap"))]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, instantiate_pair, mock_app, setup};
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw20::BalanceResponse;
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+// The anchor mock mints its aUST on "Contract #2", so a terraswap mock token is instantiated third to stand in for it
+fn setup_anchor(app: &mut App) -> (Addr, Addr) {
+    instantiate_pair(app);
+    instantiate_pair(app);
+    let aust = instantiate_pair(app);
+    assert_eq!(aust.as_str(), "Contract #2");
+    let anchor = instantiate(app, contract_anchor_mock(), &AnchorInstantiateMsg {});
+    (anchor, aust)
+}
+
+fn deposit(app: &mut App, anchor: &Addr, amount: u128) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        anchor.clone(),
+        &AnchorExecuteMsg::DepositStable {},
+        &coins(amount, "uusd"),
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+fn aust_balance(app: &App, aust: &Addr) -> u128 {
+    let balance: BalanceResponse = app
+        .query_mock(
+            aust,
+            &TerraswapQueryMsg::Balance {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+    balance.balance.u128()
+}
+
+#[test]
+fn deposits_below_the_minimum_are_rejected() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let (anchor, aust) = setup_anchor(&mut app);
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(10_000, "uusd"))
+        .unwrap();
+    set_anchor_min_deposit(Uint128::new(1_000));
+
+    assert_err_contains(
+        deposit(&mut app, &anchor, 999),
+        "Deposit of 999 is below the minimum of 1000",
+    );
+    let uusd = app.wrap().query_balance("alice", "uusd").unwrap();
+    assert_eq!(uusd.amount.u128(), 10_000);
+
+    // Above the minimum the deposit goes through and mints aUST at the 1.2 exchange rate
+    deposit(&mut app, &anchor, 1_200).unwrap();
+    assert_eq!(aust_balance(&app, &aust), 1_000);
+    let uusd = app.wrap().query_balance("alice", "uusd").unwrap();
+    assert_eq!(uusd.amount.u128(), 8_800);
+}
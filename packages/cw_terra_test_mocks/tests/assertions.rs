@@ -0,0 +1,130 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{pair_fixture, swap, OWNER};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, AppResponse, Executor};
+use terraswap::pair::SimulationResponse;
+
+fn simulate(app: &App, pair: &Addr, denom: &str, amount: u128) -> Uint128 {
+    let simulation: SimulationResponse = app
+        .query_mock(
+            pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: asset(native(denom), Uint128::new(amount)),
+            },
+        )
+        .unwrap();
+    simulation.return_amount
+}
+
+#[test]
+fn swap_return_amount_through_the_helpers() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let expected = simulate(&app, &pair, "uusd", 1_000);
+
+    let res = swap(&mut app, &pair, "alice", "uusd", 1_000);
+    assert_attribute(&res, "return_amount", &expected.to_string());
+    assert_attribute(&res, "action", "swap");
+    assert_eq!(attribute_value(&res, "sender"), Some("alice".to_string()));
+    assert_eq!(attribute_value(&res, "no_such_key"), None);
+}
+
+#[test]
+#[should_panic(expected = "attribute return_amount")]
+fn assert_attribute_panics_on_a_different_value() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let res = swap(&mut app, &pair, "alice", "uusd", 1_000);
+    assert_attribute(&res, "return_amount", "0");
+}
+
+fn mint(app: &mut App, token: &Addr, recipient: &str, amount: u128) -> AppResponse {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &TerraswapExecuteMsg::Mint {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .unwrap()
+}
+
+#[test]
+fn mint_emits_the_whole_wasm_event() {
+    let (_guard, mut app, token) = pair_fixture();
+    let res = mint(&mut app, &token, "alice", 100);
+    assert_event(
+        &res,
+        "wasm",
+        &[
+            ("_contract_addr", token.as_str()),
+            ("action", "mint"),
+            ("to", "alice"),
+            ("amount", "100"),
+        ],
+    );
+    // A subset of the attributes is enough
+    assert_event(&res, "wasm", &[("action", "mint")]);
+}
+
+#[test]
+#[should_panic(expected = "no wasm event with attributes")]
+fn assert_event_panics_when_one_attribute_differs() {
+    let (_guard, mut app, token) = pair_fixture();
+    let res = mint(&mut app, &token, "alice", 100);
+    assert_event(&res, "wasm", &[("action", "mint"), ("amount", "99")]);
+}
+
+fn execute(app: &mut App, token: &Addr, sender: &str, msg: TerraswapExecuteMsg) {
+    app.execute_contract(Addr::unchecked(sender), token.clone(), &msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn supply_stays_consistent_through_mints_burns_and_transfers() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+    mint(&mut app, &token, "bob", 50);
+    assert_supply_consistent(&app, &token);
+
+    execute(
+        &mut app,
+        &token,
+        "alice",
+        TerraswapExecuteMsg::Burn {
+            amount: Uint128::new(30),
+        },
+    );
+    execute(
+        &mut app,
+        &token,
+        "alice",
+        TerraswapExecuteMsg::Transfer {
+            recipient: "carol".to_string(),
+            amount: Uint128::new(20),
+        },
+    );
+    assert_supply_consistent(&app, &token);
+}
+
+#[test]
+#[should_panic(expected = "sum of balances 2 != total_supply 3")]
+fn assert_supply_consistent_catches_a_mismatch() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 1);
+    mint(&mut app, &token, "bob", 1);
+    // Each balance of 1.5 rounds down on its own while the supply of 3 doesn't
+    execute(
+        &mut app,
+        &token,
+        "owner",
+        TerraswapExecuteMsg::Rebase {
+            factor: Decimal::percent(150),
+        },
+    );
+    assert_supply_consistent(&app, &token);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{pair_fixture, swap, OWNER};
+use cosmwasm_std::{Addr, Uint128};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::Executor;
+
+#[test]
+fn client_queries_a_live_mock() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let client = TerraswapMockClient::new(pair.clone());
+
+    assert_eq!(client.pool(&app).unwrap(), mock_pool_info());
+    assert_eq!(client.pair(&app).unwrap(), mock_pair_info());
+
+    // The client follows the pool as it moves
+    let offer_asset = asset(native("uusd"), Uint128::new(1_000));
+    let simulation = client.simulate(&app, offer_asset).unwrap();
+    swap(&mut app, &pair, "alice", "uusd", 1_000);
+    let pool = client.pool(&app).unwrap();
+    assert_eq!(pool.assets[1].amount.u128(), 11_000);
+    assert_eq!(
+        pool.assets[0].amount,
+        mock_pool_info().assets[0].amount - simulation.return_amount
+    );
+
+    assert!(client.balance(&app, "alice").unwrap().balance.is_zero());
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        pair,
+        &TerraswapExecuteMsg::Mint {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(250),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(client.balance(&app, "alice").unwrap().balance.u128(), 250);
+}
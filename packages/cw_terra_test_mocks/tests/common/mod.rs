@@ -0,0 +1,306 @@
+// Setup shared by the integration tests. Every test file pulls it in with `mod common;` and only uses part of it.
+#![allow(dead_code)]
+
+use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, ContractResult, Empty, Reply, ReplyOn, Response, StdError, StdResult,
+    SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use cw721::Cw721ReceiveMsg;
+use cw_storage_plus::Item;
+use cw_terra_test_mocks::prelude::MockGuard;
+use lazy_static::lazy_static;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use terra_multi_test::{App, BankKeeper, Contract, ContractWrapper, Executor, TerraMockQuerier};
+use terraswap::pair::PoolResponse;
+
+pub const OWNER: &str = "owner";
+
+lazy_static! {
+    // cargo runs the tests of a file in parallel threads while the mocks keep their configuration in process wide
+    // statics, so each test holds this for as long as it runs
+    static ref SERIAL: Mutex<()> = Mutex::new(());
+}
+
+// Held for the whole test, the test runs alone and starts and ends with the mock defaults
+pub struct TestGuard {
+    // Dropped first so the defaults are back before the next test gets the lock
+    _mock: MockGuard,
+    _serial: MutexGuard<'static, ()>,
+}
+
+// Call first thing in every test: `let _guard = setup();`
+pub fn setup() -> TestGuard {
+    // A failed test poisons the lock, the tests after it still get to run
+    let serial = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+    TestGuard {
+        _mock: MockGuard::new(),
+        _serial: serial,
+    }
+}
+
+pub fn mock_app() -> App {
+    let env = mock_env();
+    let api = MockApi::default();
+    let bank = BankKeeper::new();
+    let custom_querier = TerraMockQuerier::new(MockQuerier::new(&[]));
+    App::new(api, env.block, bank, MockStorage::new(), custom_querier)
+}
+
+// Store the contract and instantiate it from OWNER, without an admin
+pub fn instantiate<M: Serialize>(
+    app: &mut App,
+    contract: Box<dyn Contract<Empty>>,
+    msg: &M,
+) -> Addr {
+    let code_id = app.store_code(contract);
+    app.instantiate_contract(code_id, Addr::unchecked(OWNER), msg, &[], "mock", None)
+        .unwrap()
+}
+
+// A terraswap mock pair with the default token/uusd reserves of mock_pool_info
+#[cfg(feature = "terraswap")]
+pub fn instantiate_pair(app: &mut App) -> Addr {
+    instantiate(
+        app,
+        cw_terra_test_mocks::prelude::contract_terraswap_mock(),
+        &cw_terra_test_mocks::prelude::TerraswapInstantiateMsg::default(),
+    )
+}
+
+// The start of most pair tests, `let (_guard, mut app, pair) = pair_fixture();`: the guard of setup, a fresh app and a
+// pair with the default reserves
+#[cfg(feature = "terraswap")]
+pub fn pair_fixture() -> (TestGuard, App, Addr) {
+    let guard = setup();
+    let mut app = mock_app();
+    let pair = instantiate_pair(&mut app);
+    (guard, app, pair)
+}
+
+// A terraswap mock pair trading the two assets, with the reserve amounts of mock_pool_info
+#[cfg(feature = "terraswap")]
+pub fn instantiate_pair_of(app: &mut App, asset_infos: [terraswap::asset::AssetInfo; 2]) -> Addr {
+    instantiate(
+        app,
+        cw_terra_test_mocks::prelude::contract_terraswap_mock(),
+        &cw_terra_test_mocks::prelude::TerraswapInstantiateMsg {
+            asset_infos: Some(asset_infos),
+        },
+    )
+}
+
+// A bare Swap of `amount` of the native `denom` from `sender`, it moves the pool's reserves
+#[cfg(feature = "terraswap")]
+pub fn swap(
+    app: &mut App,
+    pair: &Addr,
+    sender: &str,
+    denom: &str,
+    amount: u128,
+) -> terra_multi_test::AppResponse {
+    use cw_terra_test_mocks::prelude::{asset, native, TerraswapExecuteMsg};
+    app.execute_contract(
+        Addr::unchecked(sender),
+        pair.clone(),
+        &TerraswapExecuteMsg::Swap {
+            offer_asset: asset(native(denom), Uint128::new(amount)),
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+        &[],
+    )
+    .unwrap()
+}
+
+// Unwrap the error of a call that should have failed and check its message
+pub fn assert_err_contains<T: Debug, E: Display>(result: Result<T, E>, expected: &str) {
+    match result {
+        Ok(value) => panic!(
+            "expected an error containing {:?}, got {:?}",
+            expected, value
+        ),
+        Err(err) => {
+            let message = err.to_string();
+            assert!(
+                message.contains(expected),
+                "error {:?} doesn't contain {:?}",
+                message,
+                expected
+            );
+        }
+    }
+}
+
+//
+// A stand-in for the contract under test, it calls the mocks and handles their callbacks like a consumer would
+//
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CallerInstantiateMsg {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallerExecuteMsg {
+    // Count the call in the caller's own storage, then execute `msg` on `contract`
+    Forward {
+        contract: String,
+        msg: Binary,
+    },
+    // Execute `msg` on `contract` as a submessage replied to on success, the reply is kept for LastReply
+    ForwardWithReply {
+        contract: String,
+        msg: Binary,
+        id: u64,
+    },
+    // Query the pool of `contract` and fail with the caller's own error if the mock overflowed
+    CheckPool {
+        contract: String,
+    },
+    // The cw20 receive hook, kept for Received
+    Receive(Cw20ReceiveMsg),
+    // The cw721 receive hook, kept for ReceivedNfts
+    ReceiveNft(Cw721ReceiveMsg),
+    // The transfer hook of the terraswap mock token, kept for Hooks
+    TransferHook {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallerQueryMsg {
+    Counter {},
+    Received {},
+    ReceivedNfts {},
+    Hooks {},
+    LastReply {},
+    // The pool of `contract` decoded into a PoolResponse, like a consumer reading reserves
+    Pool { contract: String },
+}
+
+// The reply the caller got last, as (id, data)
+pub type LastReply = Option<(u64, Option<Binary>)>;
+
+// The transfer hooks the caller got, as (sender, recipient, amount)
+pub type Hooks = Vec<(String, String, Uint128)>;
+
+// The error the caller maps an overflow in the mock's math to
+pub const CALLER_OVERFLOW_ERROR: &str = "Caller: the pool math overflowed";
+
+const COUNTER: Item<u64> = Item::new("counter");
+const RECEIVED: Item<Vec<Cw20ReceiveMsg>> = Item::new("received");
+const RECEIVED_NFTS: Item<Vec<Cw721ReceiveMsg>> = Item::new("received_nfts");
+const HOOKS: Item<Hooks> = Item::new("hooks");
+const LAST_REPLY: Item<LastReply> = Item::new("last_reply");
+
+pub fn contract_caller() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, _, _, msg: CallerExecuteMsg| -> StdResult<Response> {
+            match msg {
+                CallerExecuteMsg::Forward { contract, msg } => {
+                    let counter = COUNTER.may_load(deps.storage)?.unwrap_or_default();
+                    COUNTER.save(deps.storage, &(counter + 1))?;
+                    Ok(Response::new().add_message(WasmMsg::Execute {
+                        contract_addr: contract,
+                        msg,
+                        funds: vec![],
+                    }))
+                }
+                CallerExecuteMsg::ForwardWithReply { contract, msg, id } => Ok(Response::new()
+                    .add_submessage(SubMsg {
+                        id,
+                        msg: WasmMsg::Execute {
+                            contract_addr: contract,
+                            msg,
+                            funds: vec![],
+                        }
+                        .into(),
+                        gas_limit: None,
+                        reply_on: ReplyOn::Success,
+                    })),
+                CallerExecuteMsg::CheckPool { contract } => {
+                    let pool: StdResult<PoolResponse> = deps
+                        .querier
+                        .query_wasm_smart(contract, &terraswap::pair::QueryMsg::Pool {});
+                    match pool {
+                        Ok(_) => Ok(Response::new().add_attribute("action", "check_pool")),
+                        Err(err) if err.to_string().contains("Overflow") => {
+                            Err(StdError::generic_err(CALLER_OVERFLOW_ERROR))
+                        }
+                        Err(err) => Err(err),
+                    }
+                }
+                CallerExecuteMsg::Receive(received) => {
+                    let mut all = RECEIVED.may_load(deps.storage)?.unwrap_or_default();
+                    all.push(received);
+                    RECEIVED.save(deps.storage, &all)?;
+                    Ok(Response::new().add_attribute("action", "received"))
+                }
+                CallerExecuteMsg::ReceiveNft(received) => {
+                    let mut all = RECEIVED_NFTS.may_load(deps.storage)?.unwrap_or_default();
+                    all.push(received);
+                    RECEIVED_NFTS.save(deps.storage, &all)?;
+                    Ok(Response::new().add_attribute("action", "received_nft"))
+                }
+                CallerExecuteMsg::TransferHook {
+                    sender,
+                    recipient,
+                    amount,
+                } => {
+                    let mut hooks = HOOKS.may_load(deps.storage)?.unwrap_or_default();
+                    hooks.push((sender, recipient, amount));
+                    HOOKS.save(deps.storage, &hooks)?;
+                    Ok(Response::new().add_attribute("action", "transfer_hook"))
+                }
+            }
+        },
+        |_, _, _, _: CallerInstantiateMsg| -> StdResult<Response> { Ok(Response::default()) },
+        |deps, _, msg: CallerQueryMsg| -> StdResult<Binary> {
+            match msg {
+                CallerQueryMsg::Counter {} => {
+                    to_binary(&COUNTER.may_load(deps.storage)?.unwrap_or_default())
+                }
+                CallerQueryMsg::Received {} => {
+                    to_binary(&RECEIVED.may_load(deps.storage)?.unwrap_or_default())
+                }
+                CallerQueryMsg::ReceivedNfts {} => {
+                    to_binary(&RECEIVED_NFTS.may_load(deps.storage)?.unwrap_or_default())
+                }
+                CallerQueryMsg::Hooks {} => {
+                    to_binary(&HOOKS.may_load(deps.storage)?.unwrap_or_default())
+                }
+                CallerQueryMsg::LastReply {} => {
+                    to_binary(&LAST_REPLY.may_load(deps.storage)?.unwrap_or_default())
+                }
+                CallerQueryMsg::Pool { contract } => {
+                    let pool: PoolResponse = deps
+                        .querier
+                        .query_wasm_smart(contract, &terraswap::pair::QueryMsg::Pool {})?;
+                    to_binary(&pool)
+                }
+            }
+        },
+    )
+    .with_reply(|deps, _, reply: Reply| -> StdResult<Response> {
+        let data = match reply.result {
+            ContractResult::Ok(result) => result.data,
+            ContractResult::Err(err) => return Err(StdError::generic_err(err)),
+        };
+        LAST_REPLY.save(deps.storage, &Some((reply.id, data)))?;
+        Ok(Response::new().add_attribute("action", "caller_reply"))
+    });
+    Box::new(contract)
+}
+
+pub fn instantiate_caller(app: &mut App) -> Addr {
+    instantiate(app, contract_caller(), &CallerInstantiateMsg {})
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{setup, OWNER};
+use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{Addr, Uint128};
+use cw_terra_test_mocks::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terra_multi_test::{App, BankKeeper, Executor, TerraMockQuerier};
+
+// Stands in for a chain's custom message like TerraMsgWrapper
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainMsg {
+    Noop {},
+}
+
+fn custom_app() -> App<ChainMsg> {
+    let env = mock_env();
+    let custom_querier = TerraMockQuerier::new(MockQuerier::new(&[]));
+    App::new(
+        MockApi::default(),
+        env.block,
+        BankKeeper::new(),
+        MockStorage::new(),
+        custom_querier,
+    )
+}
+
+#[test]
+fn mock_runs_in_an_app_with_a_custom_message_type() {
+    let _guard = setup();
+    let mut app = custom_app();
+    let code_id = app.store_code(contract_terraswap_mock_custom::<ChainMsg>());
+    let pair = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &TerraswapInstantiateMsg::default(),
+            &[],
+            "mock",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        pair.clone(),
+        &TerraswapExecuteMsg::Swap {
+            offer_asset: asset(native("uusd"), Uint128::new(1_000)),
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let pool: PoolResponse = app
+        .wrap()
+        .query_wasm_smart(pair, &TerraswapQueryMsg::Pool {})
+        .unwrap();
+    assert_ne!(pool, mock_pool_info());
+    assert_eq!(pool.assets[1].amount, Uint128::new(11_000));
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "cw1")]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, mock_app, setup};
+use cosmwasm_std::{coins, Addr, BankMsg};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+// A proxy administered by alice holding 1000uusd
+fn instantiate_proxy(app: &mut App, mutable: bool) -> Addr {
+    let proxy = instantiate(
+        app,
+        contract_cw1_whitelist_mock(),
+        &Cw1InstantiateMsg {
+            admins: vec!["alice".to_string()],
+            mutable,
+        },
+    );
+    app.init_bank_balance(&proxy, coins(1_000, "uusd")).unwrap();
+    proxy
+}
+
+fn pay_dave(app: &mut App, proxy: &Addr, sender: &str) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        proxy.clone(),
+        &Cw1ExecuteMsg::Execute {
+            msgs: vec![BankMsg::Send {
+                to_address: "dave".to_string(),
+                amount: coins(100, "uusd"),
+            }
+            .into()],
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+fn dave_balance(app: &App) -> u128 {
+    app.wrap()
+        .query_balance("dave", "uusd")
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn admin_forwards_and_non_admin_is_rejected() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let proxy = instantiate_proxy(&mut app, false);
+
+    pay_dave(&mut app, &proxy, "alice").unwrap();
+    assert_eq!(dave_balance(&app), 100);
+    let left = app.wrap().query_balance(&proxy, "uusd").unwrap();
+    assert_eq!(left.amount.u128(), 900);
+
+    assert_err_contains(
+        pay_dave(&mut app, &proxy, "mallory"),
+        &MockError::Unauthorized {}.to_string(),
+    );
+    assert_eq!(dave_balance(&app), 100);
+}
+
+#[test]
+fn frozen_admin_list_can_no_longer_change() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let proxy = instantiate_proxy(&mut app, true);
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        proxy.clone(),
+        &Cw1ExecuteMsg::UpdateAdmins {
+            admins: vec!["alice".to_string(), "bob".to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+    pay_dave(&mut app, &proxy, "bob").unwrap();
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        proxy.clone(),
+        &Cw1ExecuteMsg::Freeze {},
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        proxy.clone(),
+        &Cw1ExecuteMsg::UpdateAdmins {
+            admins: vec!["alice".to_string()],
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    let admins: AdminListResponse = app.query_mock(&proxy, &Cw1QueryMsg::AdminList {}).unwrap();
+    assert_eq!(admins.admins, vec!["alice", "bob"]);
+    assert!(!admins.mutable);
+}
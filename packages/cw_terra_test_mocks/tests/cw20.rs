@@ -0,0 +1,626 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{
+    assert_err_contains, instantiate_caller, instantiate_pair, pair_fixture, CallerQueryMsg, Hooks,
+    OWNER,
+};
+use cosmwasm_std::{attr, to_binary, Addr, Binary, Decimal, ReplyOn, Uint128};
+use cw20::{AllAccountsResponse, BalanceResponse, Cw20ReceiveMsg, TokenInfoResponse};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, AppResponse, Executor};
+
+fn mint(app: &mut App, token: &Addr, recipient: &str, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &TerraswapExecuteMsg::Mint {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn send(
+    app: &mut App,
+    token: &Addr,
+    sender: &str,
+    contract: &Addr,
+    amount: u128,
+    msg: Binary,
+) -> Result<AppResponse, String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        token.clone(),
+        &TerraswapExecuteMsg::Send {
+            contract: contract.to_string(),
+            amount: Uint128::new(amount),
+            msg,
+        },
+        &[],
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn balance(app: &App, token: &Addr, account: &str) -> u128 {
+    let balance: BalanceResponse = app
+        .query_mock(
+            token,
+            &TerraswapQueryMsg::Balance {
+                address: account.to_string(),
+            },
+        )
+        .unwrap();
+    balance.balance.u128()
+}
+
+#[test]
+fn send_with_an_empty_payload_is_a_plain_deposit() {
+    let (_guard, mut app, token) = pair_fixture();
+    let receiver = instantiate_pair(&mut app);
+    mint(&mut app, &token, "alice", 100);
+
+    let res = send(&mut app, &token, "alice", &receiver, 40, Binary::default()).unwrap();
+    assert_eq!(balance(&app, &token, "alice"), 60);
+    assert_eq!(balance(&app, &token, receiver.as_str()), 40);
+    // No hook went to the receiver
+    assert!(res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .all(|attribute| attribute.value != "pong"));
+}
+
+#[test]
+fn send_with_a_ping_payload_gets_a_pong() {
+    let (_guard, mut app, token) = pair_fixture();
+    let receiver = instantiate_pair(&mut app);
+    mint(&mut app, &token, "alice", 100);
+
+    let ping = to_binary(&PingMsg {
+        payload: "hello".to_string(),
+    })
+    .unwrap();
+    let res = send(&mut app, &token, "alice", &receiver, 40, ping).unwrap();
+    assert_event(&res, "wasm", &[("action", "send"), ("amount", "40")]);
+    assert_event(&res, "wasm", &[("action", "pong")]);
+    assert_eq!(balance(&app, &token, receiver.as_str()), 40);
+}
+
+#[test]
+fn send_with_an_unknown_hook_lists_the_expected_shapes() {
+    let (_guard, mut app, token) = pair_fixture();
+    let receiver = instantiate_pair(&mut app);
+    mint(&mut app, &token, "alice", 100);
+
+    let unknown = Binary::from(br#"{"stake":{}}"#.as_ref());
+    assert_err_contains(
+        send(&mut app, &token, "alice", &receiver, 40, unknown),
+        &MockError::UnknownReceiveHook {
+            received: r#"{"stake":{}}"#.to_string(),
+            expected: RECEIVE_HOOK_SHAPES.join(", "),
+        }
+        .to_string(),
+    );
+    assert_eq!(balance(&app, &token, "alice"), 100);
+}
+
+fn transfer(
+    app: &mut App,
+    token: &Addr,
+    sender: &str,
+    recipient: &str,
+    amount: u128,
+) -> Result<AppResponse, String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        token.clone(),
+        &TerraswapExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn set_frozen(app: &mut App, token: &Addr, addr: &str, frozen: bool) {
+    let msg = if frozen {
+        TerraswapExecuteMsg::FreezeAccount {
+            addr: addr.to_string(),
+        }
+    } else {
+        TerraswapExecuteMsg::UnfreezeAccount {
+            addr: addr.to_string(),
+        }
+    };
+    app.execute_contract(Addr::unchecked(OWNER), token.clone(), &msg, &[])
+        .unwrap();
+}
+
+fn is_frozen(app: &App, token: &Addr, addr: &str) -> bool {
+    let frozen: IsFrozenResponse = app
+        .query_mock(
+            token,
+            &TerraswapQueryMsg::IsFrozen {
+                addr: addr.to_string(),
+            },
+        )
+        .unwrap();
+    frozen.frozen
+}
+
+#[test]
+fn frozen_accounts_can_neither_send_nor_receive() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+    mint(&mut app, &token, "bob", 100);
+    assert!(!is_frozen(&app, &token, "alice"));
+
+    set_frozen(&mut app, &token, "alice", true);
+    assert!(is_frozen(&app, &token, "alice"));
+    let frozen = MockError::AccountFrozen {
+        account: "alice".to_string(),
+    }
+    .to_string();
+    assert_err_contains(transfer(&mut app, &token, "alice", "bob", 10), &frozen);
+    assert_err_contains(transfer(&mut app, &token, "bob", "alice", 10), &frozen);
+    assert_eq!(balance(&app, &token, "alice"), 100);
+
+    set_frozen(&mut app, &token, "alice", false);
+    assert!(!is_frozen(&app, &token, "alice"));
+    transfer(&mut app, &token, "alice", "bob", 10).unwrap();
+    transfer(&mut app, &token, "bob", "alice", 5).unwrap();
+    assert_eq!(balance(&app, &token, "alice"), 95);
+    assert_eq!(balance(&app, &token, "bob"), 105);
+}
+
+#[test]
+fn strict_validation_rejects_malformed_recipients() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+
+    // Plain names pass by default
+    transfer(&mut app, &token, "alice", "bob", 10).unwrap();
+
+    set_strict_address_validation(true);
+    for recipient in &["bob", "not an address", "terra1short"] {
+        assert_err_contains(
+            transfer(&mut app, &token, "alice", recipient, 10),
+            &MockError::InvalidAddress {
+                addr: recipient.to_string(),
+            }
+            .to_string(),
+        );
+    }
+    transfer(&mut app, &token, "alice", "terra1qpzry9x8", 10).unwrap();
+    assert_eq!(balance(&app, &token, "terra1qpzry9x8"), 10);
+    assert_eq!(balance(&app, &token, "alice"), 80);
+}
+
+#[test]
+fn mint_and_send_reject_invalid_addresses() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+    set_strict_address_validation(true);
+    let invalid = MockError::InvalidAddress {
+        addr: "not an address".to_string(),
+    }
+    .to_string();
+
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            token.clone(),
+            &TerraswapExecuteMsg::Mint {
+                recipient: "not an address".to_string(),
+                amount: Uint128::new(50),
+            },
+            &[],
+        ),
+        &invalid,
+    );
+    assert_eq!(total_supply(&app, &token).u128(), 100);
+
+    assert_err_contains(
+        send(
+            &mut app,
+            &token,
+            "alice",
+            &Addr::unchecked("not an address"),
+            10,
+            Binary::default(),
+        ),
+        &invalid,
+    );
+    assert_eq!(balance(&app, &token, "alice"), 100);
+}
+
+fn rebase(app: &mut App, token: &Addr, factor: Decimal) -> Result<AppResponse, String> {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &TerraswapExecuteMsg::Rebase { factor },
+        &[],
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn total_supply(app: &App, token: &Addr) -> Uint128 {
+    let token_info: TokenInfoResponse = app
+        .query_mock(token, &TerraswapQueryMsg::TokenInfo {})
+        .unwrap();
+    token_info.total_supply
+}
+
+#[test]
+fn rebase_scales_balances_and_supply() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+    mint(&mut app, &token, "bob", 50);
+    let supply = total_supply(&app, &token);
+
+    rebase(&mut app, &token, Decimal::percent(150)).unwrap();
+    assert_eq!(balance(&app, &token, "alice"), 150);
+    assert_eq!(balance(&app, &token, "bob"), 75);
+    assert_eq!(
+        total_supply(&app, &token),
+        supply.multiply_ratio(3u128, 2u128)
+    );
+
+    // Factors stack up
+    rebase(&mut app, &token, Decimal::percent(200)).unwrap();
+    assert_eq!(balance(&app, &token, "alice"), 300);
+    assert_eq!(balance(&app, &token, "bob"), 150);
+    assert_eq!(total_supply(&app, &token), supply * Uint128::new(3));
+
+    assert_err_contains(
+        rebase(&mut app, &token, Decimal::zero()),
+        &MockError::InvalidRebaseFactor {}.to_string(),
+    );
+    assert_eq!(balance(&app, &token, "alice"), 300);
+}
+
+fn all_accounts(
+    app: &App,
+    token: &Addr,
+    start_after: Option<&str>,
+    limit: Option<u32>,
+) -> Vec<String> {
+    let accounts: AllAccountsResponse = app
+        .query_mock(
+            token,
+            &TerraswapQueryMsg::AllAccounts {
+                start_after: start_after.map(|account| account.to_string()),
+                limit,
+            },
+        )
+        .unwrap();
+    accounts.accounts
+}
+
+#[test]
+fn all_accounts_are_sorted_whatever_the_credit_order() {
+    let (_guard, mut app, token) = pair_fixture();
+    for account in &["zed", "alice", "mike", "bob"] {
+        mint(&mut app, &token, account, 10);
+    }
+
+    assert_eq!(
+        all_accounts(&app, &token, None, None),
+        vec!["alice", "bob", "mike", "zed"]
+    );
+    // Pages follow the same order
+    assert_eq!(
+        all_accounts(&app, &token, None, Some(2)),
+        vec!["alice", "bob"]
+    );
+    assert_eq!(
+        all_accounts(&app, &token, Some("bob"), Some(2)),
+        vec!["mike", "zed"]
+    );
+    assert!(all_accounts(&app, &token, Some("zed"), None).is_empty());
+}
+
+#[test]
+fn sends_to_the_token_itself_only_fail_when_rejected() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+    let transfer_from = |app: &mut App| {
+        app.execute_contract(
+            Addr::unchecked("spender"),
+            token.clone(),
+            &TerraswapExecuteMsg::TransferFrom {
+                owner: "alice".to_string(),
+                recipient: token.to_string(),
+                amount: Uint128::new(10),
+            },
+            &[],
+        )
+        .map_err(|err| err.to_string())
+    };
+
+    // Off by default, the tokens end up stuck in the token contract
+    transfer(&mut app, &token, "alice", token.as_str(), 10).unwrap();
+    send(&mut app, &token, "alice", &token, 10, Binary::default()).unwrap();
+    transfer_from(&mut app).unwrap();
+    assert_eq!(balance(&app, &token, token.as_str()), 30);
+
+    set_reject_self_send(true);
+    let self_send = MockError::SelfSend {}.to_string();
+    assert_err_contains(
+        transfer(&mut app, &token, "alice", token.as_str(), 10),
+        &self_send,
+    );
+    assert_err_contains(
+        send(&mut app, &token, "alice", &token, 10, Binary::default()),
+        &self_send,
+    );
+    assert_err_contains(transfer_from(&mut app), &self_send);
+    assert_eq!(balance(&app, &token, "alice"), 70);
+    // Other recipients are fine
+    transfer(&mut app, &token, "alice", "bob", 10).unwrap();
+}
+
+#[test]
+fn configured_receive_response_replaces_the_pong() {
+    let (_guard, mut app, token) = pair_fixture();
+    let receiver = instantiate_pair(&mut app);
+    mint(&mut app, &token, "alice", 100);
+    let ping = to_binary(&PingMsg {
+        payload: "hello".to_string(),
+    })
+    .unwrap();
+
+    set_receive_response(
+        vec![attr("status", "accepted")],
+        Some(to_binary("receipt").unwrap()),
+    );
+    // The token replies so the receiver's data comes back as the token's own
+    set_send_reply_on(ReplyOn::Success);
+    let res = send(&mut app, &token, "alice", &receiver, 40, ping.clone()).unwrap();
+    assert_event(&res, "wasm", &[("status", "accepted")]);
+    assert_eq!(res.data, Some(to_binary("receipt").unwrap()));
+    assert!(res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .all(|attribute| attribute.value != "pong"));
+
+    clear_receive_response();
+    let res = send(&mut app, &token, "alice", &receiver, 10, ping).unwrap();
+    assert_event(&res, "wasm", &[("action", "pong")]);
+}
+
+#[test]
+fn partial_send_debits_only_the_sent_amount() {
+    let (_guard, mut app, token) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+    mint(&mut app, &token, "alice", 100);
+    let hook = to_binary(&"deposit").unwrap();
+
+    send(&mut app, &token, "alice", &caller, 30, hook.clone()).unwrap();
+    assert_eq!(balance(&app, &token, "alice"), 70);
+    assert_eq!(balance(&app, &token, caller.as_str()), 30);
+    let received: Vec<Cw20ReceiveMsg> = app
+        .query_mock(&caller, &CallerQueryMsg::Received {})
+        .unwrap();
+    assert_eq!(
+        received,
+        vec![Cw20ReceiveMsg {
+            sender: "alice".to_string(),
+            amount: Uint128::new(30),
+            msg: hook,
+        }]
+    );
+}
+
+#[test]
+fn transfer_hook_receiver_sees_every_transfer_and_send() {
+    let (_guard, mut app, token) = pair_fixture();
+    let receiver = instantiate_pair(&mut app);
+    let caller = instantiate_caller(&mut app);
+    mint(&mut app, &token, "alice", 100);
+
+    // No hook by default
+    transfer(&mut app, &token, "alice", "bob", 10).unwrap();
+    let hooks: Hooks = app.query_mock(&caller, &CallerQueryMsg::Hooks {}).unwrap();
+    assert!(hooks.is_empty());
+
+    set_transfer_hook(caller.to_string());
+    transfer(&mut app, &token, "alice", "bob", 20).unwrap();
+    send(&mut app, &token, "alice", &receiver, 30, Binary::default()).unwrap();
+    let hooks: Hooks = app.query_mock(&caller, &CallerQueryMsg::Hooks {}).unwrap();
+    assert_eq!(
+        hooks,
+        vec![
+            ("alice".to_string(), "bob".to_string(), Uint128::new(20)),
+            ("alice".to_string(), receiver.to_string(), Uint128::new(30)),
+        ]
+    );
+
+    // A receiver that errors reverts the transfer it was called for
+    set_transfer_hook(receiver.to_string());
+    transfer(&mut app, &token, "alice", "bob", 5).unwrap_err();
+    assert_eq!(balance(&app, &token, "alice"), 40);
+    assert_eq!(balance(&app, &token, "bob"), 30);
+}
+
+fn detailed_balance(app: &App, token: &Addr, account: &str) -> DetailedBalanceResponse {
+    app.query_mock(
+        token,
+        &TerraswapQueryMsg::DetailedBalance {
+            address: account.to_string(),
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn locked_part_of_a_balance_cannot_move() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+    set_locked("alice".to_string(), Uint128::new(60));
+    assert_eq!(
+        detailed_balance(&app, &token, "alice"),
+        DetailedBalanceResponse {
+            available: Uint128::new(40),
+            locked: Uint128::new(60),
+        }
+    );
+
+    assert_err_contains(
+        transfer(&mut app, &token, "alice", "bob", 41),
+        &MockError::BalanceLocked {
+            amount: "41".to_string(),
+            available: "40".to_string(),
+        }
+        .to_string(),
+    );
+    transfer(&mut app, &token, "alice", "bob", 40).unwrap();
+    assert_eq!(balance(&app, &token, "alice"), 60);
+    // What is left is all locked
+    assert_err_contains(
+        transfer(&mut app, &token, "alice", "bob", 1),
+        &MockError::BalanceLocked {
+            amount: "1".to_string(),
+            available: "0".to_string(),
+        }
+        .to_string(),
+    );
+
+    // Unlocking frees it again
+    set_locked("alice".to_string(), Uint128::zero());
+    transfer(&mut app, &token, "alice", "bob", 60).unwrap();
+    assert_eq!(balance(&app, &token, "bob"), 100);
+}
+
+#[test]
+fn minting_past_the_max_is_an_overflow_error() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", u128::MAX);
+
+    // The supply can't take one more, the error comes back instead of a panic
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            token.clone(),
+            &TerraswapExecuteMsg::Mint {
+                recipient: "bob".to_string(),
+                amount: Uint128::new(1),
+            },
+            &[],
+        ),
+        &format!("Cannot Add with {} and 1", u128::MAX),
+    );
+    assert_eq!(balance(&app, &token, "alice"), u128::MAX);
+    assert_eq!(total_supply(&app, &token).u128(), u128::MAX);
+}
+
+#[test]
+fn paused_token_moves_nothing_until_resumed() {
+    let (_guard, mut app, token) = pair_fixture();
+    let receiver = instantiate_pair(&mut app);
+    mint(&mut app, &token, "alice", 100);
+    let paused = MockError::TokenPaused {}.to_string();
+
+    set_cw20_paused(true);
+    assert_err_contains(transfer(&mut app, &token, "alice", "bob", 10), &paused);
+    assert_err_contains(
+        send(&mut app, &token, "alice", &receiver, 10, Binary::default()),
+        &paused,
+    );
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            token.clone(),
+            &TerraswapExecuteMsg::TransferFrom {
+                owner: "alice".to_string(),
+                recipient: "bob".to_string(),
+                amount: Uint128::new(10),
+            },
+            &[],
+        ),
+        &paused,
+    );
+    // The queries keep answering
+    assert_eq!(balance(&app, &token, "alice"), 100);
+    assert_eq!(total_supply(&app, &token).u128(), 100);
+
+    set_cw20_paused(false);
+    transfer(&mut app, &token, "alice", "bob", 10).unwrap();
+    send(&mut app, &token, "alice", &receiver, 10, Binary::default()).unwrap();
+    assert_eq!(balance(&app, &token, "alice"), 80);
+    assert_eq!(balance(&app, &token, "bob"), 10);
+}
+
+#[test]
+fn underfunded_and_untracked_accounts_cannot_move_more_than_they_hold() {
+    let (_guard, mut app, token) = pair_fixture();
+    mint(&mut app, &token, "alice", 100);
+    let dummy = mock_balance_info().balance.u128();
+    let insufficient = |balance: u128, amount: u128| {
+        MockError::InsufficientBalance {
+            balance: balance.to_string(),
+            amount: amount.to_string(),
+        }
+        .to_string()
+    };
+    let transfer_from = |app: &mut App, owner: &str, amount: u128| {
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            token.clone(),
+            &TerraswapExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: "bob".to_string(),
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+    };
+    let burn = |app: &mut App, sender: &str, amount: u128| {
+        app.execute_contract(
+            Addr::unchecked(sender),
+            token.clone(),
+            &TerraswapExecuteMsg::Burn {
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+    };
+
+    assert_err_contains(
+        transfer(&mut app, &token, "alice", "bob", 101),
+        &insufficient(100, 101),
+    );
+    assert_err_contains(
+        transfer_from(&mut app, "alice", 101),
+        &insufficient(100, 101),
+    );
+    assert_err_contains(burn(&mut app, "alice", 101), &insufficient(100, 101));
+    assert_eq!(balance(&app, &token, "alice"), 100);
+
+    // Nothing was credited to mallory, the dummy balance is all there is to move
+    assert_err_contains(
+        transfer(&mut app, &token, "mallory", "bob", dummy + 1),
+        &insufficient(dummy, dummy + 1),
+    );
+    assert_err_contains(
+        transfer_from(&mut app, "mallory", dummy + 1),
+        &insufficient(dummy, dummy + 1),
+    );
+    assert_err_contains(
+        burn(&mut app, "mallory", dummy + 1),
+        &insufficient(dummy, dummy + 1),
+    );
+    transfer(&mut app, &token, "mallory", "carol", dummy).unwrap();
+    assert_eq!(balance(&app, &token, "mallory"), 0);
+    assert_eq!(balance(&app, &token, "carol"), dummy);
+    assert_err_contains(
+        transfer(&mut app, &token, "mallory", "carol", 1),
+        &insufficient(0, 1),
+    );
+}
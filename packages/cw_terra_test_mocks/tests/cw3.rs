@@ -0,0 +1,216 @@
+#![cfg(feature = "cw3")]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, mock_app, setup};
+use cosmwasm_std::{coins, Addr, BankMsg, CosmosMsg, Decimal, Empty};
+use cw0::Duration;
+use cw3::{Status, Vote};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+const VOTING_PERIOD: u64 = 10;
+
+fn voter(addr: &str, weight: u64) -> Voter {
+    Voter {
+        addr: addr.to_string(),
+        weight,
+    }
+}
+
+// A multisig of alice (1), bob (2) and carol (3) that needs a yes weight of `required_weight`
+fn instantiate_multisig(app: &mut App, required_weight: u64) -> Addr {
+    instantiate(
+        app,
+        contract_cw3_mock(),
+        &Cw3InstantiateMsg {
+            voters: vec![voter("alice", 1), voter("bob", 2), voter("carol", 3)],
+            required_weight,
+            max_voting_period: Duration::Height(VOTING_PERIOD),
+        },
+    )
+}
+
+fn pay_dave() -> Vec<CosmosMsg<Empty>> {
+    vec![BankMsg::Send {
+        to_address: "dave".to_string(),
+        amount: coins(100, "uusd"),
+    }
+    .into()]
+}
+
+fn propose(app: &mut App, multisig: &Addr, proposer: &str) -> u64 {
+    let res = app
+        .execute_contract(
+            Addr::unchecked(proposer),
+            multisig.clone(),
+            &Cw3ExecuteMsg::Propose {
+                title: "Pay dave".to_string(),
+                description: "Send dave 100uusd".to_string(),
+                msgs: pay_dave(),
+            },
+            &[],
+        )
+        .unwrap();
+    attribute_value(&res, "proposal_id")
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+fn vote(app: &mut App, multisig: &Addr, proposal_id: u64, voter: &str, vote: Vote) {
+    app.execute_contract(
+        Addr::unchecked(voter),
+        multisig.clone(),
+        &Cw3ExecuteMsg::Vote { proposal_id, vote },
+        &[],
+    )
+    .unwrap();
+}
+
+fn status(app: &App, multisig: &Addr, proposal_id: u64) -> Status {
+    let proposal: ProposalResponse = app
+        .query_mock(multisig, &Cw3QueryMsg::Proposal { proposal_id })
+        .unwrap();
+    proposal.status
+}
+
+#[test]
+fn propose_vote_and_execute() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let multisig = instantiate_multisig(&mut app, 4);
+    app.init_bank_balance(&multisig, coins(1_000, "uusd"))
+        .unwrap();
+
+    let proposal_id = propose(&mut app, &multisig, "alice");
+    assert_eq!(status(&app, &multisig, proposal_id), Status::Open);
+
+    // Non-voters can't take part
+    app.execute_contract(
+        Addr::unchecked("mallory"),
+        multisig.clone(),
+        &Cw3ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        },
+        &[],
+    )
+    .unwrap_err();
+    // An open proposal can't be executed yet
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        multisig.clone(),
+        &Cw3ExecuteMsg::Execute { proposal_id },
+        &[],
+    )
+    .unwrap_err();
+
+    vote(&mut app, &multisig, proposal_id, "carol", Vote::Yes);
+    assert_eq!(status(&app, &multisig, proposal_id), Status::Passed);
+
+    app.execute_contract(
+        Addr::unchecked("bob"),
+        multisig.clone(),
+        &Cw3ExecuteMsg::Execute { proposal_id },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(status(&app, &multisig, proposal_id), Status::Executed);
+
+    // The wrapped bank send fired from the multisig's funds
+    let dave = app.wrap().query_balance("dave", "uusd").unwrap();
+    assert_eq!(dave.amount.u128(), 100);
+    let left = app.wrap().query_balance(&multisig, "uusd").unwrap();
+    assert_eq!(left.amount.u128(), 900);
+}
+
+#[test]
+fn passed_proposal_expires_unexecuted() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let multisig = instantiate_multisig(&mut app, 4);
+    app.init_bank_balance(&multisig, coins(1_000, "uusd"))
+        .unwrap();
+
+    let proposal_id = propose(&mut app, &multisig, "alice");
+    vote(&mut app, &multisig, proposal_id, "carol", Vote::Yes);
+    assert_eq!(status(&app, &multisig, proposal_id), Status::Passed);
+
+    advance_blocks(&mut app, VOTING_PERIOD + 1);
+    assert_eq!(status(&app, &multisig, proposal_id), Status::Rejected);
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            multisig.clone(),
+            &Cw3ExecuteMsg::Execute { proposal_id },
+            &[],
+        ),
+        "Proposal has expired",
+    );
+    let dave = app.wrap().query_balance("dave", "uusd").unwrap();
+    assert_eq!(dave.amount.u128(), 0);
+}
+
+// alice (1) proposes and bob (2) votes yes, 3 of the total weight of 6
+fn status_after_alice_and_bob(threshold: Threshold) -> Status {
+    let mut app = mock_app();
+    // The instantiation weight would never pass, the threshold set below is what decides
+    let multisig = instantiate_multisig(&mut app, 100);
+    set_cw3_threshold(threshold);
+    let proposal_id = propose(&mut app, &multisig, "alice");
+    vote(&mut app, &multisig, proposal_id, "bob", Vote::Yes);
+    status(&app, &multisig, proposal_id)
+}
+
+#[test]
+fn absolute_count_passes_at_exactly_the_count() {
+    let _guard = setup();
+    assert_eq!(
+        status_after_alice_and_bob(Threshold::AbsoluteCount { weight: 3 }),
+        Status::Passed
+    );
+    assert_eq!(
+        status_after_alice_and_bob(Threshold::AbsoluteCount { weight: 4 }),
+        Status::Open
+    );
+}
+
+#[test]
+fn absolute_percentage_around_the_boundary() {
+    let _guard = setup();
+    // 50% of 6 is exactly the 3 voted
+    assert_eq!(
+        status_after_alice_and_bob(Threshold::AbsolutePercentage {
+            percentage: Decimal::percent(50)
+        }),
+        Status::Passed
+    );
+    // 51% of 6 rounds up to 4
+    assert_eq!(
+        status_after_alice_and_bob(Threshold::AbsolutePercentage {
+            percentage: Decimal::percent(51)
+        }),
+        Status::Open
+    );
+}
+
+#[test]
+fn threshold_quorum_needs_the_quorum_to_vote() {
+    let _guard = setup();
+    // 3 of 6 voted, all yes
+    assert_eq!(
+        status_after_alice_and_bob(Threshold::ThresholdQuorum {
+            threshold: Decimal::percent(50),
+            quorum: Decimal::percent(50),
+        }),
+        Status::Passed
+    );
+    assert_eq!(
+        status_after_alice_and_bob(Threshold::ThresholdQuorum {
+            threshold: Decimal::percent(50),
+            quorum: Decimal::percent(60),
+        }),
+        Status::Open
+    );
+}
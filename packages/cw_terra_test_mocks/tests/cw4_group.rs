@@ -0,0 +1,132 @@
+#![cfg(feature = "cw4")]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, mock_app, setup, OWNER};
+use cosmwasm_std::Addr;
+use cw4::{Member, MemberListResponse, MemberResponse, TotalWeightResponse};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::Executor;
+
+fn member(addr: &str, weight: u64) -> Member {
+    Member {
+        addr: addr.to_string(),
+        weight,
+    }
+}
+
+#[test]
+fn update_members_tracks_weights_and_listing() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let group = instantiate(&mut app, contract_cw4_group_mock(), &Cw4InstantiateMsg {});
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        group.clone(),
+        &Cw4ExecuteMsg::UpdateMembers {
+            remove: vec![],
+            add: vec![member("carol", 3), member("alice", 1), member("bob", 2)],
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        group.clone(),
+        &Cw4ExecuteMsg::UpdateMembers {
+            remove: vec!["bob".to_string()],
+            add: vec![],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let total: TotalWeightResponse = app
+        .query_mock(&group, &Cw4QueryMsg::TotalWeight {})
+        .unwrap();
+    assert_eq!(total.weight, 4);
+
+    let bob: MemberResponse = app
+        .query_mock(
+            &group,
+            &Cw4QueryMsg::Member {
+                addr: "bob".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(bob.weight, None);
+
+    let list: MemberListResponse = app
+        .query_mock(
+            &group,
+            &Cw4QueryMsg::ListMembers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(list.members, vec![member("alice", 1), member("carol", 3)]);
+
+    let page: MemberListResponse = app
+        .query_mock(
+            &group,
+            &Cw4QueryMsg::ListMembers {
+                start_after: Some("alice".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(page.members, vec![member("carol", 3)]);
+}
+
+#[test]
+fn re_adding_a_member_overwrites_its_weight() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let group = instantiate(&mut app, contract_cw4_group_mock(), &Cw4InstantiateMsg {});
+
+    for weight in [5u64, 2u64].iter() {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            group.clone(),
+            &Cw4ExecuteMsg::UpdateMembers {
+                remove: vec![],
+                add: vec![member("alice", *weight)],
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let total: TotalWeightResponse = app
+        .query_mock(&group, &Cw4QueryMsg::TotalWeight {})
+        .unwrap();
+    assert_eq!(total.weight, 2);
+}
+
+#[test]
+fn total_weight_overflow_is_an_error() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let group = instantiate(&mut app, contract_cw4_group_mock(), &Cw4InstantiateMsg {});
+
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            group.clone(),
+            &Cw4ExecuteMsg::UpdateMembers {
+                remove: vec![],
+                add: vec![member("alice", u64::MAX), member("bob", 1)],
+            },
+            &[],
+        ),
+        "Overflow",
+    );
+
+    // Nothing of the failed update was kept
+    let total: TotalWeightResponse = app
+        .query_mock(&group, &Cw4QueryMsg::TotalWeight {})
+        .unwrap();
+    assert_eq!(total.weight, 0);
+}
@@ -0,0 +1,302 @@
+#![cfg(feature = "cw721")]
+
+mod common;
+
+use common::{
+    assert_err_contains, instantiate, instantiate_caller, mock_app, setup, CallerQueryMsg, OWNER,
+};
+use cosmwasm_std::{to_binary, Addr, Binary};
+use cw721::{Cw721ReceiveMsg, NumTokensResponse, OwnerOfResponse, TokensResponse};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+fn instantiate_nft(app: &mut App) -> Addr {
+    instantiate(app, contract_cw721_mock(), &Cw721InstantiateMsg {})
+}
+
+fn mint(app: &mut App, nft: &Addr, token_id: &str, owner: &str) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        nft.clone(),
+        &Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn owner_of(app: &App, nft: &Addr, token_id: &str) -> String {
+    let owner: OwnerOfResponse = app
+        .query_mock(
+            nft,
+            &Cw721QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    owner.owner
+}
+
+#[test]
+fn mint_transfer_and_query_owner() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let nft = instantiate_nft(&mut app);
+
+    mint(&mut app, &nft, "punk1", "alice");
+    assert_eq!(owner_of(&app, &nft, "punk1"), "alice");
+
+    // Token ids can only be minted once
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            nft.clone(),
+            &Cw721ExecuteMsg::Mint {
+                token_id: "punk1".to_string(),
+                owner: "bob".to_string(),
+            },
+            &[],
+        ),
+        &MockError::TokenClaimed {}.to_string(),
+    );
+
+    // Only the owner can move it
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            nft.clone(),
+            &Cw721ExecuteMsg::TransferNft {
+                recipient: "bob".to_string(),
+                token_id: "punk1".to_string(),
+            },
+            &[],
+        ),
+        &MockError::Unauthorized {}.to_string(),
+    );
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        nft.clone(),
+        &Cw721ExecuteMsg::TransferNft {
+            recipient: "bob".to_string(),
+            token_id: "punk1".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(owner_of(&app, &nft, "punk1"), "bob");
+}
+
+#[test]
+fn send_nft_fires_the_receive_hook() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let nft = instantiate_nft(&mut app);
+    let caller = instantiate_caller(&mut app);
+    mint(&mut app, &nft, "punk1", "alice");
+
+    let msg = to_binary(&"list_for_sale").unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        nft.clone(),
+        &Cw721ExecuteMsg::SendNft {
+            contract: caller.to_string(),
+            token_id: "punk1".to_string(),
+            msg: msg.clone(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(owner_of(&app, &nft, "punk1"), caller.to_string());
+    let received: Vec<Cw721ReceiveMsg> = app
+        .query_mock(&caller, &CallerQueryMsg::ReceivedNfts {})
+        .unwrap();
+    assert_eq!(
+        received,
+        vec![Cw721ReceiveMsg {
+            sender: "alice".to_string(),
+            token_id: "punk1".to_string(),
+            msg,
+        }]
+    );
+}
+
+#[test]
+fn failed_receive_hook_reverts_the_send() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let nft = instantiate_nft(&mut app);
+    // Another nft mock has no receive hook at all
+    let not_a_receiver = instantiate_nft(&mut app);
+    mint(&mut app, &nft, "punk1", "alice");
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        nft.clone(),
+        &Cw721ExecuteMsg::SendNft {
+            contract: not_a_receiver.to_string(),
+            token_id: "punk1".to_string(),
+            msg: Binary::default(),
+        },
+        &[],
+    )
+    .unwrap_err();
+    assert_eq!(owner_of(&app, &nft, "punk1"), "alice");
+}
+
+fn transfer(app: &mut App, nft: &Addr, sender: &str, recipient: &str, token_id: &str) -> bool {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        nft.clone(),
+        &Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        },
+        &[],
+    )
+    .is_ok()
+}
+
+#[test]
+fn approved_spenders_and_operators_can_transfer() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let nft = instantiate_nft(&mut app);
+    mint(&mut app, &nft, "punk1", "alice");
+    mint(&mut app, &nft, "punk2", "alice");
+
+    // bob gets punk1 only
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        nft.clone(),
+        &Cw721ExecuteMsg::Approve {
+            spender: "bob".to_string(),
+            token_id: "punk1".to_string(),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let approvals: ApprovalsResponse = app
+        .query_mock(
+            &nft,
+            &Cw721QueryMsg::Approvals {
+                token_id: "punk1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(approvals.approvals.len(), 1);
+    assert_eq!(approvals.approvals[0].spender, "bob");
+    assert!(!transfer(&mut app, &nft, "bob", "bob", "punk2"));
+    assert!(transfer(&mut app, &nft, "bob", "dave", "punk1"));
+    assert_eq!(owner_of(&app, &nft, "punk1"), "dave");
+    // The approval went with the previous owner
+    assert!(!transfer(&mut app, &nft, "bob", "bob", "punk1"));
+
+    // carol operates every token of alice, she moves punk2 without alice sending it
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        nft.clone(),
+        &Cw721ExecuteMsg::ApproveAll {
+            operator: "carol".to_string(),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    assert!(transfer(&mut app, &nft, "carol", "erin", "punk2"));
+    assert_eq!(owner_of(&app, &nft, "punk2"), "erin");
+    // But not the tokens of other owners
+    assert!(!transfer(&mut app, &nft, "carol", "carol", "punk1"));
+}
+
+#[test]
+fn revoked_operators_can_no_longer_transfer() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let nft = instantiate_nft(&mut app);
+    mint(&mut app, &nft, "punk1", "alice");
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        nft.clone(),
+        &Cw721ExecuteMsg::ApproveAll {
+            operator: "carol".to_string(),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        nft.clone(),
+        &Cw721ExecuteMsg::RevokeAll {
+            operator: "carol".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert!(!transfer(&mut app, &nft, "carol", "carol", "punk1"));
+    assert_eq!(owner_of(&app, &nft, "punk1"), "alice");
+}
+
+fn tokens(
+    app: &App,
+    nft: &Addr,
+    owner: &str,
+    start_after: Option<&str>,
+    limit: u32,
+) -> Vec<String> {
+    let tokens: TokensResponse = app
+        .query_mock(
+            nft,
+            &Cw721QueryMsg::Tokens {
+                owner: owner.to_string(),
+                start_after: start_after.map(String::from),
+                limit: Some(limit),
+            },
+        )
+        .unwrap();
+    tokens.tokens
+}
+
+#[test]
+fn enumerate_tokens_per_owner_in_pages() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let nft = instantiate_nft(&mut app);
+    for (token_id, owner) in [
+        ("a1", "alice"),
+        ("b1", "bob"),
+        ("a2", "alice"),
+        ("b2", "bob"),
+        ("a3", "alice"),
+    ]
+    .iter()
+    {
+        mint(&mut app, &nft, token_id, owner);
+    }
+
+    let count: NumTokensResponse = app.query_mock(&nft, &Cw721QueryMsg::NumTokens {}).unwrap();
+    assert_eq!(count.count, 5);
+
+    assert_eq!(tokens(&app, &nft, "alice", None, 2), vec!["a1", "a2"]);
+    assert_eq!(tokens(&app, &nft, "alice", Some("a2"), 2), vec!["a3"]);
+    assert_eq!(tokens(&app, &nft, "bob", None, 1), vec!["b1"]);
+    assert_eq!(tokens(&app, &nft, "bob", Some("b1"), 1), vec!["b2"]);
+    assert!(tokens(&app, &nft, "bob", Some("b2"), 1).is_empty());
+
+    let all: TokensResponse = app
+        .query_mock(
+            &nft,
+            &Cw721QueryMsg::AllTokens {
+                start_after: Some("a3".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(all.tokens, vec!["b1", "b2"]);
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "factory")]
+
+mod common;
+
+use common::{instantiate, mock_app, setup};
+use cosmwasm_std::Addr;
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::App;
+use terraswap::asset::AssetInfo;
+
+fn pair(denom0: &str, denom1: &str) -> PairResponse {
+    PairResponse {
+        asset_infos: [native(denom0), native(denom1)],
+        contract_addr: format!("pair_{}_{}", denom0, denom1),
+        liquidity_token: format!("lp_{}_{}", denom0, denom1),
+    }
+}
+
+fn pairs(
+    app: &App,
+    factory: &Addr,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+    asset_info: Option<AssetInfo>,
+) -> Vec<PairResponse> {
+    let response: PairsResponse = app
+        .query_mock(
+            factory,
+            &FactoryQueryMsg::Pairs {
+                start_after,
+                limit,
+                asset_info,
+            },
+        )
+        .unwrap();
+    response.pairs
+}
+
+#[test]
+fn pairs_filtered_by_asset_and_paged() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let factory = instantiate(
+        &mut app,
+        contract_factory_mock(),
+        &FactoryInstantiateMsg {
+            pairs: vec![
+                pair("uluna", "uusd"),
+                pair("ukrw", "uusd"),
+                pair("uatom", "uosmo"),
+                pair("ukrw", "uluna"),
+            ],
+        },
+    );
+
+    // Listed in pair key order, ukrw:uluna before uluna:uusd
+    let luna_pairs = pairs(&app, &factory, None, None, Some(native("uluna")));
+    assert_eq!(
+        luna_pairs,
+        vec![pair("ukrw", "uluna"), pair("uluna", "uusd")]
+    );
+    assert_eq!(pairs(&app, &factory, None, None, None).len(), 4);
+    assert_eq!(
+        pairs(&app, &factory, None, None, Some(native("uscrt"))),
+        vec![]
+    );
+
+    // A page of one at a time walks the same matches
+    let first = pairs(&app, &factory, None, Some(1), Some(native("uluna")));
+    assert_eq!(first, vec![pair("ukrw", "uluna")]);
+    let second = pairs(
+        &app,
+        &factory,
+        Some(first[0].asset_infos.clone()),
+        Some(1),
+        Some(native("uluna")),
+    );
+    assert_eq!(second, vec![pair("uluna", "uusd")]);
+    let done = pairs(
+        &app,
+        &factory,
+        Some(second[0].asset_infos.clone()),
+        Some(1),
+        Some(native("uluna")),
+    );
+    assert_eq!(done, vec![]);
+}
@@ -0,0 +1,231 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{
+    assert_err_contains, instantiate_caller, pair_fixture, CallerExecuteMsg, CallerQueryMsg,
+    CALLER_OVERFLOW_ERROR,
+};
+use cosmwasm_std::{
+    to_binary, Addr, OverflowError, OverflowOperation, StdError, StdResult, Uint128,
+};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+use terraswap::pair::SimulationResponse;
+
+fn query_pool(app: &App, pair: &Addr) -> StdResult<PoolResponse> {
+    app.query_mock(pair, &TerraswapQueryMsg::Pool {})
+}
+
+#[test]
+fn fail_next_query_fires_once() {
+    let (_guard, app, pair) = pair_fixture();
+
+    set_fail_next_query(true);
+    assert_err_contains(query_pool(&app, &pair), "Mock query failure");
+    assert_eq!(get_fail_next_queries(), 0);
+    assert_eq!(query_pool(&app, &pair).unwrap(), mock_pool_info());
+}
+
+#[test]
+fn fail_next_queries_counts_down() {
+    let (_guard, app, pair) = pair_fixture();
+
+    set_fail_next_queries(2);
+    query_pool(&app, &pair).unwrap_err();
+    query_pool(&app, &pair).unwrap_err();
+    query_pool(&app, &pair).unwrap();
+}
+
+// The caller counts the call, then swaps 1000uusd on the pair
+fn forward_swap(app: &mut App, caller: &Addr, pair: &Addr) -> Result<(), String> {
+    let swap = TerraswapExecuteMsg::Swap {
+        offer_asset: asset(native("uusd"), Uint128::new(1_000)),
+        belief_price: None,
+        max_spread: None,
+        to: None,
+    };
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        caller.clone(),
+        &CallerExecuteMsg::Forward {
+            contract: pair.to_string(),
+            msg: to_binary(&swap).unwrap(),
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+#[test]
+fn failed_downstream_execute_rolls_back_the_caller() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+
+    set_fail_next_execute(true);
+    assert_err_contains(
+        forward_swap(&mut app, &caller, &pair),
+        "Mock execute failure",
+    );
+    let counter: u64 = app
+        .query_mock(&caller, &CallerQueryMsg::Counter {})
+        .unwrap();
+    assert_eq!(counter, 0);
+    assert_eq!(query_pool(&app, &pair).unwrap(), mock_pool_info());
+
+    // The toggle fired, the same call goes through now
+    forward_swap(&mut app, &caller, &pair).unwrap();
+    let counter: u64 = app
+        .query_mock(&caller, &CallerQueryMsg::Counter {})
+        .unwrap();
+    assert_eq!(counter, 1);
+    assert_ne!(query_pool(&app, &pair).unwrap(), mock_pool_info());
+}
+
+fn check_pool(app: &mut App, caller: &Addr, pair: &Addr) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        caller.clone(),
+        &CallerExecuteMsg::CheckPool {
+            contract: pair.to_string(),
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+#[test]
+fn injected_overflow_is_mapped_by_the_caller() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+
+    set_injected_error(Some(StdError::overflow(OverflowError::new(
+        OverflowOperation::Mul,
+        u128::MAX,
+        2u128,
+    ))));
+    assert!(has_injected_error());
+    assert_err_contains(check_pool(&mut app, &caller, &pair), CALLER_OVERFLOW_ERROR);
+
+    // It fired once, the next check goes through
+    assert!(!has_injected_error());
+    check_pool(&mut app, &caller, &pair).unwrap();
+}
+
+#[test]
+fn other_injected_errors_pass_through_the_caller() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+
+    set_injected_error(Some(StdError::not_found("pool")));
+    assert_err_contains(check_pool(&mut app, &caller, &pair), "pool not found");
+}
+
+#[test]
+fn only_the_configured_kind_fails() {
+    let (_guard, mut app, pair) = pair_fixture();
+    set_fail_on_execute(TerraswapExecuteMsgKind::Swap);
+
+    let offer_asset = asset(native("uusd"), Uint128::new(1_000));
+    let simulation: SimulationResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+            },
+        )
+        .unwrap();
+    assert!(!simulation.return_amount.is_zero());
+
+    let swap = TerraswapExecuteMsg::Swap {
+        offer_asset,
+        belief_price: None,
+        max_spread: None,
+        to: None,
+    };
+    assert_err_contains(
+        app.execute_contract(Addr::unchecked("alice"), pair.clone(), &swap, &[]),
+        "Mock execute failure on Swap",
+    );
+    // Other messages still go through, and the Swap does once cleared
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        pair.clone(),
+        &TerraswapExecuteMsg::ResetVolume {},
+        &[],
+    )
+    .unwrap();
+    clear_fail_on();
+    app.execute_contract(Addr::unchecked("alice"), pair.clone(), &swap, &[])
+        .unwrap();
+}
+
+#[test]
+fn every_third_call_fails() {
+    let (_guard, app, pair) = pair_fixture();
+
+    set_fail_every(3);
+    let outcomes: Vec<bool> = (0..5).map(|_| query_pool(&app, &pair).is_ok()).collect();
+    assert_eq!(outcomes, vec![true, true, false, true, true]);
+    // The next call is the sixth
+    query_pool(&app, &pair).unwrap_err();
+
+    set_fail_every(0);
+    assert!((0..5).all(|_| query_pool(&app, &pair).is_ok()));
+}
+
+#[test]
+fn malformed_responses_are_parse_errors_for_the_caller() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+    let caller_pool = CallerQueryMsg::Pool {
+        contract: pair.to_string(),
+    };
+
+    set_return_malformed(true);
+    // The query itself succeeds, it's decoding the bytes that fails
+    assert_err_contains(query_pool(&app, &pair), "Error parsing into type");
+    assert_err_contains(
+        app.query_mock::<PoolResponse, _>(&caller, &caller_pool),
+        "Error parsing into type",
+    );
+
+    set_return_malformed(false);
+    let pool: PoolResponse = app.query_mock(&caller, &caller_pool).unwrap();
+    assert_eq!(pool, mock_pool_info());
+}
+
+#[test]
+fn custom_error_message_reaches_the_caller() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+
+    set_error_message("pair is halted".to_string());
+    set_fail_next_query(true);
+    assert_err_contains(query_pool(&app, &pair), "pair is halted");
+    set_fail_next_execute(true);
+    assert_err_contains(forward_swap(&mut app, &caller, &pair), "pair is halted");
+
+    clear_error_message();
+    set_fail_next_query(true);
+    assert_err_contains(query_pool(&app, &pair), "Mock query failure");
+}
+
+#[test]
+fn unreachable_contract_fails_both_ways_until_turned_off() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+    let unreachable = MockError::Unreachable {}.to_string();
+
+    set_contract_unreachable(true);
+    for _ in 0..2 {
+        assert_err_contains(query_pool(&app, &pair), &unreachable);
+        assert_err_contains(forward_swap(&mut app, &caller, &pair), &unreachable);
+    }
+
+    set_contract_unreachable(false);
+    query_pool(&app, &pair).unwrap();
+    forward_swap(&mut app, &caller, &pair).unwrap();
+}
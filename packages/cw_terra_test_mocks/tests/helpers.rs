@@ -0,0 +1,287 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{assert_err_contains, instantiate_pair, pair_fixture, swap, OWNER};
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{coins, from_slice, to_vec, Addr, BlockInfo, Decimal, Uint128};
+use cw20::{BalanceResponse, TokenInfoResponse};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+use terraswap::pair::SimulationResponse;
+
+fn k(pool: &PoolResponse) -> Uint256 {
+    Uint256::from(pool.assets[0].amount) * Uint256::from(pool.assets[1].amount)
+}
+
+#[test]
+fn drain_pool_stops_at_the_fraction() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let start = mock_pool_info();
+
+    let drained = drain_pool(&mut app, &pair, Decimal::percent(10));
+    assert!(drained.assets[1].amount <= Uint128::new(1_000));
+    assert!(!drained.assets[1].amount.is_zero());
+    assert!(drained.assets[0].amount > start.assets[0].amount);
+    // The commission kept in the pool only grows the product
+    assert!(k(&drained) >= k(&start));
+
+    // The pool it returns is the pool the pair reports
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, drained);
+}
+
+#[test]
+#[should_panic(expected = "drain_pool fraction must be above 0 and below 1")]
+fn drain_pool_refuses_to_empty_the_pool() {
+    let (_guard, mut app, pair) = pair_fixture();
+    drain_pool(&mut app, &pair, Decimal::zero());
+}
+
+#[test]
+fn block_info_query_follows_the_app() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let block_info = |app: &App| -> BlockInfo {
+        app.query_mock(&pair, &TerraswapQueryMsg::BlockInfo {})
+            .unwrap()
+    };
+
+    let start = block_info(&app);
+    assert_eq!(start, app.block_info());
+    advance_blocks(&mut app, 5);
+    let later = block_info(&app);
+    assert_eq!(later.height, start.height + 5);
+    assert_eq!(later.time, start.time.plus_seconds(25));
+    assert_eq!(later.chain_id, start.chain_id);
+}
+
+#[test]
+fn funded_accounts_can_swap_native_legs() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let mut app = MockAppBuilder::new()
+        .with_funded_account("alice", coins(1_000, "uusd"))
+        // The pair pays the return out of its own bank balance
+        .with_funded_account(pair.as_str(), coins(10_000, "token"))
+        .build(app);
+    let offer_asset = asset(native("uusd"), Uint128::new(1_000));
+    let expected: SimulationResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+            },
+        )
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        pair.clone(),
+        &TerraswapExecuteMsg::Swap {
+            offer_asset,
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+        &coins(1_000, "uusd"),
+    )
+    .unwrap();
+    let uusd = app.wrap().query_balance("alice", "uusd").unwrap();
+    assert!(uusd.amount.is_zero());
+    let token = app.wrap().query_balance("alice", "token").unwrap();
+    assert_eq!(token.amount, expected.return_amount);
+    assert!(!token.amount.is_zero());
+}
+
+#[test]
+fn advance_time_moves_only_the_clock() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let start = app.block_info();
+
+    advance_time(&mut app, 90);
+    let block: BlockInfo = app
+        .query_mock(&pair, &TerraswapQueryMsg::BlockInfo {})
+        .unwrap();
+    assert_eq!(block.height, start.height);
+    assert_eq!(block.time, start.time.plus_seconds(90));
+
+    // Both together, as a test waiting out an unbonding period would
+    advance_blocks(&mut app, 2);
+    advance_time(&mut app, 10);
+    let block: BlockInfo = app
+        .query_mock(&pair, &TerraswapQueryMsg::BlockInfo {})
+        .unwrap();
+    assert_eq!(block.height, start.height + 2);
+    assert_eq!(block.time, start.time.plus_seconds(90 + 10 + 10));
+}
+
+#[test]
+fn dump_reflects_the_configured_pair() {
+    let (_guard, mut app, pair) = pair_fixture();
+    schedule_reserves(
+        pair.to_string(),
+        0,
+        asset(native("token"), Uint128::new(5_000)),
+        asset(native("uusd"), Uint128::new(20_000)),
+    );
+    app.wasm_sudo(
+        pair.clone(),
+        &TerraswapSudoMsg::UpdateConfig {
+            commission_rate: Decimal::percent(1),
+        },
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        pair.clone(),
+        &TerraswapExecuteMsg::Mint {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(100),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let config = dump_mock_config(&app, &pair);
+    assert_eq!(config.pool.assets[0].amount.u128(), 5_000);
+    assert_eq!(config.pool.assets[1].amount.u128(), 20_000);
+    assert_eq!(config.commission_rate, Decimal::percent(1));
+    assert_eq!(
+        config.balances,
+        vec![("alice".to_string(), Uint128::new(100))]
+    );
+    assert_eq!(config.token_info.total_supply.u128(), 100);
+    // It prints as JSON for a failing test's output
+    let json = String::from_utf8(to_vec(&config).unwrap()).unwrap();
+    assert!(json.contains(r#""commission_rate":"0.01""#));
+}
+
+// A cw20/uusd pool with a 0.5% commission and two holders
+const FIXTURE: &str = r#"{
+    "pool": {
+        "assets": [
+            {"info": {"token": {"contract_addr": "terra1token"}}, "amount": "7000000"},
+            {"info": {"native_token": {"denom": "uusd"}}, "amount": "3500000"}
+        ],
+        "total_share": "4949747"
+    },
+    "commission_rate": "0.005",
+    "commission_split": [40, 10],
+    "token_info": {"name": "Snapshot LP", "symbol": "SLP", "decimals": 6, "total_supply": "4949747"},
+    "balances": [["alice", "4000000"], ["bob", "949747"]]
+}"#;
+
+#[test]
+fn loaded_fixture_replaces_the_pair_state() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let other_pair = instantiate_pair(&mut app);
+    let other_before = dump_mock_config(&app, &other_pair);
+
+    load_mock_config_from_json(&mut app, &pair, FIXTURE).unwrap();
+    let expected: MockConfig = from_slice(FIXTURE.as_bytes()).unwrap();
+    // Everything in the fixture is what the pair answers with now
+    assert_eq!(dump_mock_config(&app, &pair), expected);
+    let pair_info: PairResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::Pair { asset_infos: None })
+        .unwrap();
+    assert_eq!(
+        pair_info.asset_infos,
+        [token("terra1token"), native("uusd")]
+    );
+    let balance: BalanceResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Balance {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(balance.balance.u128(), 4_000_000);
+
+    // The other pair and the global defaults are untouched
+    assert_eq!(dump_mock_config(&app, &other_pair), other_before);
+    assert_eq!(get_commission_split(), (30, 0));
+}
+
+#[test]
+fn loaded_fixture_drops_the_swap_records_and_pending_reserves() {
+    let (_guard, mut app, pair) = pair_fixture();
+    set_commission_split(25, 5);
+    swap(&mut app, &pair, "alice", "uusd", 1_000);
+    swap(&mut app, &pair, "bob", "token", 500);
+    schedule_reserves(
+        pair.to_string(),
+        app.block_info().height + 1,
+        asset(native("uusd"), Uint128::new(1)),
+        asset(token("terra1token"), Uint128::new(1)),
+    );
+
+    load_mock_config_from_json(&mut app, &pair, FIXTURE).unwrap();
+    let history: KHistoryResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::KHistory {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(history.entries.is_empty());
+    let volume: VolumeResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::Volume {})
+        .unwrap();
+    assert!(volume.offer_volume.is_empty());
+    assert!(volume.ask_volume.is_empty());
+    let treasury: TreasuryResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::Treasury {})
+        .unwrap();
+    assert!(treasury.fees.is_empty());
+
+    // The reserves scheduled before the load never land on the loaded pool
+    advance_blocks(&mut app, 1);
+    let expected: MockConfig = from_slice(FIXTURE.as_bytes()).unwrap();
+    assert_eq!(dump_mock_config(&app, &pair).pool, expected.pool);
+}
+
+#[test]
+fn bad_fixture_changes_nothing() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let before = dump_mock_config(&app, &pair);
+
+    let mismatched = FIXTURE.replace(r#""0.005""#, r#""0.003""#);
+    assert_err_contains(
+        load_mock_config_from_json(&mut app, &pair, &mismatched),
+        "Invalid mock config: commission_rate 0.003 doesn't match commission_split (40, 10)",
+    );
+    // The duplicate comes after the rest of the fixture was read, none of it is written
+    let duplicated = FIXTURE.replace(r#"["bob", "949747"]"#, r#"["alice", "949747"]"#);
+    assert_err_contains(
+        load_mock_config_from_json(&mut app, &pair, &duplicated),
+        "Invalid mock config: alice has more than one balance",
+    );
+    assert_err_contains(
+        load_mock_config_from_json(&mut app, &pair, "{\"pool\": {}}"),
+        "Error parsing into type",
+    );
+    assert_eq!(dump_mock_config(&app, &pair), before);
+}
+
+#[test]
+fn query_mock_decodes_the_response_type_asked_for() {
+    let (_guard, app, pair) = pair_fixture();
+
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+    let token_info: TokenInfoResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::TokenInfo {})
+        .unwrap();
+    assert_eq!(token_info, mock_token_info());
+
+    // Asking for the wrong response type is a parse error, not a silently empty value
+    assert_err_contains(
+        app.query_mock::<PoolResponse, _>(&pair, &TerraswapQueryMsg::TokenInfo {}),
+        "Error parsing into type",
+    );
+    // As is a contract that isn't there
+    app.query_mock::<PoolResponse, _>(&Addr::unchecked("nobody"), &TerraswapQueryMsg::Pool {})
+        .unwrap_err();
+}
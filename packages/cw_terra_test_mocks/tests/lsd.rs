@@ -0,0 +1,239 @@
+#![cfg(all(feature = "lsd", feature = "terraswap"))]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, instantiate_pair, mock_app, setup};
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{coins, to_binary, Addr, Binary, Uint128};
+use cw20::BalanceResponse;
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+// The hub and, standing in for its derivative cw20, a terraswap mock
+fn setup_hub(app: &mut App) -> (Addr, Addr) {
+    let token = instantiate_pair(app);
+    let hub = instantiate(
+        app,
+        contract_lsd_mock(),
+        &LsdInstantiateMsg {
+            token: token.to_string(),
+        },
+    );
+    (hub, token)
+}
+
+fn bond(app: &mut App, hub: &Addr, staker: &str, amount: u128) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(staker),
+        hub.clone(),
+        &LsdExecuteMsg::Bond {},
+        &coins(amount, "uluna"),
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+fn send_to_hub(
+    app: &mut App,
+    hub: &Addr,
+    token: &Addr,
+    staker: &str,
+    amount: u128,
+    msg: Binary,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(staker),
+        token.clone(),
+        &TerraswapExecuteMsg::Send {
+            contract: hub.to_string(),
+            amount: Uint128::new(amount),
+            msg,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+fn withdraw(app: &mut App, hub: &Addr, staker: &str) {
+    app.execute_contract(
+        Addr::unchecked(staker),
+        hub.clone(),
+        &LsdExecuteMsg::WithdrawUnbonded {},
+        &[],
+    )
+    .unwrap();
+}
+
+fn derivative_balance(app: &App, token: &Addr, account: &str) -> u128 {
+    let balance: BalanceResponse = app
+        .query_mock(
+            token,
+            &TerraswapQueryMsg::Balance {
+                address: account.to_string(),
+            },
+        )
+        .unwrap();
+    balance.balance.u128()
+}
+
+fn uluna_balance(app: &App, account: &str) -> u128 {
+    app.wrap()
+        .query_balance(account, "uluna")
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn bond_wait_out_the_unbonding_and_withdraw() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let (hub, token) = setup_hub(&mut app);
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(1_000, "uluna"))
+        .unwrap();
+    set_unbonding_period(10);
+    set_lsd_exchange_rate(Decimal256::percent(200));
+
+    // One derivative is worth 2uluna
+    bond(&mut app, &hub, "alice", 1_000).unwrap();
+    assert_eq!(derivative_balance(&app, &token, "alice"), 500);
+    let state: StateResponse = app.query_mock(&hub, &LsdQueryMsg::State {}).unwrap();
+    assert_eq!(state.total_bond_amount.u128(), 1_000);
+    assert_eq!(state.exchange_rate, Decimal256::percent(200));
+    assert_eq!(state.unbonding_period, 10);
+
+    let unbond_height = app.block_info().height;
+    send_to_hub(
+        &mut app,
+        &hub,
+        &token,
+        "alice",
+        500,
+        to_binary(&LsdHookMsg::Unbond {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(derivative_balance(&app, &token, "alice"), 0);
+    let requests: UnbondRequestsResponse = app
+        .query_mock(
+            &hub,
+            &LsdQueryMsg::UnbondRequests {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        requests.requests,
+        vec![UnbondRequest {
+            amount: Uint128::new(1_000),
+            release_height: unbond_height + 10,
+        }]
+    );
+
+    // Still unbonding, nothing to withdraw
+    advance_blocks(&mut app, 9);
+    withdraw(&mut app, &hub, "alice");
+    assert_eq!(uluna_balance(&app, "alice"), 0);
+
+    advance_blocks(&mut app, 1);
+    withdraw(&mut app, &hub, "alice");
+    assert_eq!(uluna_balance(&app, "alice"), 1_000);
+    assert_eq!(uluna_balance(&app, hub.as_str()), 0);
+}
+
+#[test]
+fn bonding_at_a_zero_exchange_rate_errors() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let (hub, token) = setup_hub(&mut app);
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(1_000, "uluna"))
+        .unwrap();
+
+    set_lsd_exchange_rate(Decimal256::zero());
+    assert_err_contains(
+        bond(&mut app, &hub, "alice", 1_000),
+        &MockError::ZeroExchangeRate {}.to_string(),
+    );
+    assert_eq!(derivative_balance(&app, &token, "alice"), 0);
+    assert_eq!(uluna_balance(&app, "alice"), 1_000);
+}
+
+#[test]
+fn undecodable_hook_is_a_parse_error() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let (hub, token) = setup_hub(&mut app);
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(1_000, "uluna"))
+        .unwrap();
+    bond(&mut app, &hub, "alice", 1_000).unwrap();
+
+    let result = send_to_hub(
+        &mut app,
+        &hub,
+        &token,
+        "alice",
+        100,
+        to_binary(&"unbond please").unwrap(),
+    );
+    assert_err_contains(result.clone(), "Error parsing into type");
+    assert!(!result
+        .unwrap_err()
+        .contains(&MockError::Unauthorized {}.to_string()));
+    assert_eq!(derivative_balance(&app, &token, "alice"), 1_000);
+}
+
+fn unbond_requests(app: &App, hub: &Addr, staker: &str) -> Vec<UnbondRequest> {
+    let requests: UnbondRequestsResponse = app
+        .query_mock(
+            hub,
+            &LsdQueryMsg::UnbondRequests {
+                address: staker.to_string(),
+            },
+        )
+        .unwrap();
+    requests.requests
+}
+
+#[test]
+fn overlapping_unbonds_mature_separately() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let (hub, token) = setup_hub(&mut app);
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(1_000, "uluna"))
+        .unwrap();
+    set_unbonding_period(10);
+    bond(&mut app, &hub, "alice", 1_000).unwrap();
+    let unbond = to_binary(&LsdHookMsg::Unbond {}).unwrap();
+
+    let first_height = app.block_info().height;
+    send_to_hub(&mut app, &hub, &token, "alice", 300, unbond.clone()).unwrap();
+    advance_blocks(&mut app, 4);
+    send_to_hub(&mut app, &hub, &token, "alice", 200, unbond).unwrap();
+    let first = UnbondRequest {
+        amount: Uint128::new(300),
+        release_height: first_height + 10,
+    };
+    let second = UnbondRequest {
+        amount: Uint128::new(200),
+        release_height: first_height + 14,
+    };
+    assert_eq!(
+        unbond_requests(&app, &hub, "alice"),
+        vec![first, second.clone()]
+    );
+
+    // Only the first has matured
+    advance_blocks(&mut app, 6);
+    withdraw(&mut app, &hub, "alice");
+    assert_eq!(uluna_balance(&app, "alice"), 300);
+    assert_eq!(unbond_requests(&app, &hub, "alice"), vec![second]);
+
+    advance_blocks(&mut app, 4);
+    withdraw(&mut app, &hub, "alice");
+    assert_eq!(uluna_balance(&app, "alice"), 500);
+    assert_eq!(unbond_requests(&app, &hub, "alice"), vec![]);
+    // The rest is still bonded
+    assert_eq!(derivative_balance(&app, &token, "alice"), 500);
+    let state: StateResponse = app.query_mock(&hub, &LsdQueryMsg::State {}).unwrap();
+    assert_eq!(state.total_bond_amount.u128(), 500);
+}
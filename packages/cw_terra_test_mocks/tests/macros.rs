@@ -0,0 +1,50 @@
+mod common;
+
+use common::{assert_err_contains, instantiate, mock_app, setup};
+use cosmwasm_std::{Decimal, Empty};
+use cw_terra_test_mocks::canned_query_mock;
+use cw_terra_test_mocks::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum StubQueryMsg {
+    Price {},
+    Echo { value: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+struct StubPriceResponse {
+    rate: Decimal,
+}
+
+#[test]
+fn canned_query_mock_answers_each_variant() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let contract = canned_query_mock!(StubQueryMsg, {
+        StubQueryMsg::Price {} => StubPriceResponse { rate: Decimal::percent(150) },
+        StubQueryMsg::Echo { value } => value,
+    });
+    let stub = instantiate(&mut app, contract, &Empty {});
+
+    let price: StubPriceResponse = app.query_mock(&stub, &StubQueryMsg::Price {}).unwrap();
+    assert_eq!(price.rate, Decimal::percent(150));
+    let echo: String = app
+        .query_mock(
+            &stub,
+            &StubQueryMsg::Echo {
+                value: "hello".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(echo, "hello");
+
+    // The failure toggles reach the stub like any other mock
+    set_fail_next_query(true);
+    assert_err_contains(
+        app.query_mock::<StubPriceResponse, _>(&stub, &StubQueryMsg::Price {}),
+        "Mock query failure",
+    );
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{mock_app, setup, OWNER};
+use cosmwasm_std::Addr;
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+// A terraswap mock with OWNER as its admin, and the code id it can be migrated to
+fn instantiate_migratable_pair(app: &mut App) -> (Addr, u64) {
+    let code_id = app.store_code(contract_terraswap_mock());
+    let pair = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &TerraswapInstantiateMsg::default(),
+            &[],
+            "mock",
+            Some(OWNER.to_string()),
+        )
+        .unwrap();
+    (pair, code_id)
+}
+
+fn version(app: &App, pair: &Addr) -> String {
+    let version: VersionResponse = app
+        .query_mock(pair, &TerraswapQueryMsg::Version {})
+        .unwrap();
+    version.version
+}
+
+#[test]
+fn migrate_bumps_the_version() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let (pair, code_id) = instantiate_migratable_pair(&mut app);
+    assert_eq!(version(&app, &pair), env!("CARGO_PKG_VERSION"));
+
+    app.migrate_contract(
+        Addr::unchecked(OWNER),
+        pair.clone(),
+        &MigrateMsg {
+            version: "2.0.0".to_string(),
+        },
+        code_id,
+    )
+    .unwrap();
+    assert_eq!(version(&app, &pair), "2.0.0");
+    // The pool survives the migration
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+}
+
+#[test]
+fn only_the_admin_can_migrate() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let (pair, code_id) = instantiate_migratable_pair(&mut app);
+    let migrate = |app: &mut App, sender: &str| {
+        app.migrate_contract(
+            Addr::unchecked(sender),
+            pair.clone(),
+            &MigrateMsg {
+                version: "2.0.0".to_string(),
+            },
+            code_id,
+        )
+    };
+
+    migrate(&mut app, "mallory").unwrap_err();
+    assert_eq!(version(&app, &pair), env!("CARGO_PKG_VERSION"));
+
+    migrate(&mut app, OWNER).unwrap();
+    assert_eq!(version(&app, &pair), "2.0.0");
+}
+
+#[test]
+fn a_mock_without_an_admin_cant_be_migrated() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let code_id = app.store_code(contract_terraswap_mock());
+    let pair = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &TerraswapInstantiateMsg::default(),
+            &[],
+            "mock",
+            None,
+        )
+        .unwrap();
+
+    app.migrate_contract(
+        Addr::unchecked(OWNER),
+        pair.clone(),
+        &MigrateMsg {
+            version: "2.0.0".to_string(),
+        },
+        code_id,
+    )
+    .unwrap_err();
+    assert_eq!(version(&app, &pair), env!("CARGO_PKG_VERSION"));
+}
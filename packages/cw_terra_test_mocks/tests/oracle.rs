@@ -0,0 +1,96 @@
+#![cfg(feature = "oracle")]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, mock_app, setup};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::App;
+
+fn query_price(app: &App, oracle: &Addr) -> Decimal {
+    let price: PriceResponse = app
+        .query_mock(
+            oracle,
+            &OracleQueryMsg::Price {
+                base: "uluna".to_string(),
+                quote: "uusd".to_string(),
+            },
+        )
+        .unwrap();
+    price.rate
+}
+
+// Starts a walk with the seed and reads the prices of `steps` queries
+fn walk(seed: u64, steps: usize) -> Vec<Decimal> {
+    let mut app = mock_app();
+    let oracle = instantiate(&mut app, contract_oracle_mock(), &OracleInstantiateMsg {});
+    set_price(
+        "uluna".to_string(),
+        "uusd".to_string(),
+        Decimal::percent(5000),
+    );
+    set_price_walk(
+        "uluna".to_string(),
+        "uusd".to_string(),
+        seed,
+        Decimal::percent(5),
+    );
+    (0..steps).map(|_| query_price(&app, &oracle)).collect()
+}
+
+#[test]
+fn same_seed_gives_the_same_prices() {
+    let _guard = setup();
+    let first = walk(42, 20);
+    reset_mock_state();
+    let second = walk(42, 20);
+    assert_eq!(first, second);
+
+    // The walk actually moves, by at most 5% of the price per step
+    assert!(first.windows(2).any(|pair| pair[0] != pair[1]));
+    let scale = Uint128::new(1_000_000_000_000);
+    for pair in first.windows(2) {
+        let (before, after) = (scale * pair[0], scale * pair[1]);
+        let step = if after > before {
+            after - before
+        } else {
+            before - after
+        };
+        assert!(step <= before.multiply_ratio(5u128, 100u128) + Uint128::new(1));
+    }
+}
+
+#[test]
+fn different_seeds_give_different_prices() {
+    let _guard = setup();
+    let first = walk(1, 20);
+    reset_mock_state();
+    let second = walk(2, 20);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn fixed_price_without_a_walk() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let oracle = instantiate(&mut app, contract_oracle_mock(), &OracleInstantiateMsg {});
+
+    assert_err_contains(
+        app.query_mock::<PriceResponse, _>(
+            &oracle,
+            &OracleQueryMsg::Price {
+                base: "uluna".to_string(),
+                quote: "uusd".to_string(),
+            },
+        ),
+        "No price set for uluna/uusd",
+    );
+
+    set_price(
+        "uluna".to_string(),
+        "uusd".to_string(),
+        Decimal::percent(250),
+    );
+    assert_eq!(query_price(&app, &oracle), Decimal::percent(250));
+    assert_eq!(query_price(&app, &oracle), Decimal::percent(250));
+}
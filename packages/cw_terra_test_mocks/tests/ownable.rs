@@ -0,0 +1,110 @@
+#![cfg(feature = "ownable")]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, mock_app, setup, OWNER};
+use cosmwasm_std::Addr;
+use cw0::Expiration;
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+fn update(app: &mut App, ownable: &Addr, sender: &str, action: Action) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        ownable.clone(),
+        &OwnableExecuteMsg::UpdateOwnership(action),
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+fn ownership(app: &App, ownable: &Addr) -> Ownership {
+    app.query_mock(ownable, &OwnableQueryMsg::Ownership {})
+        .unwrap()
+}
+
+fn transfer_to(new_owner: &str, expiry: Option<Expiration>) -> Action {
+    Action::TransferOwnership {
+        new_owner: new_owner.to_string(),
+        expiry,
+    }
+}
+
+#[test]
+fn two_step_transfer_then_renounce() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let ownable = instantiate(
+        &mut app,
+        contract_ownable_mock(),
+        &OwnableInstantiateMsg { owner: None },
+    );
+    assert_eq!(
+        ownership(&app, &ownable).owner,
+        Some(Addr::unchecked(OWNER))
+    );
+
+    // Only the owner proposes
+    assert_err_contains(
+        update(&mut app, &ownable, "alice", transfer_to("alice", None)),
+        &MockError::NotOwner {}.to_string(),
+    );
+    update(&mut app, &ownable, OWNER, transfer_to("alice", None)).unwrap();
+    let pending = ownership(&app, &ownable);
+    assert_eq!(pending.owner, Some(Addr::unchecked(OWNER)));
+    assert_eq!(pending.pending_owner, Some(Addr::unchecked("alice")));
+
+    // Only the proposed owner accepts
+    assert_err_contains(
+        update(&mut app, &ownable, "bob", Action::AcceptOwnership),
+        &MockError::NotPendingOwner {}.to_string(),
+    );
+    update(&mut app, &ownable, "alice", Action::AcceptOwnership).unwrap();
+    assert_eq!(
+        ownership(&app, &ownable),
+        Ownership {
+            owner: Some(Addr::unchecked("alice")),
+            pending_owner: None,
+            pending_expiry: None,
+        }
+    );
+
+    // The previous owner has no say anymore, the new one can give it up
+    assert_err_contains(
+        update(&mut app, &ownable, OWNER, Action::RenounceOwnership),
+        &MockError::NotOwner {}.to_string(),
+    );
+    update(&mut app, &ownable, "alice", Action::RenounceOwnership).unwrap();
+    assert_eq!(ownership(&app, &ownable).owner, None);
+    update(&mut app, &ownable, "alice", transfer_to("bob", None)).unwrap_err();
+}
+
+#[test]
+fn pending_transfer_expires() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let ownable = instantiate(
+        &mut app,
+        contract_ownable_mock(),
+        &OwnableInstantiateMsg { owner: None },
+    );
+    let expiry = Expiration::AtHeight(app.block_info().height + 5);
+    update(
+        &mut app,
+        &ownable,
+        OWNER,
+        transfer_to("alice", Some(expiry)),
+    )
+    .unwrap();
+
+    advance_blocks(&mut app, 5);
+    assert_err_contains(
+        update(&mut app, &ownable, "alice", Action::AcceptOwnership),
+        &MockError::TransferExpired {}.to_string(),
+    );
+    assert_eq!(
+        ownership(&app, &ownable).owner,
+        Some(Addr::unchecked(OWNER))
+    );
+}
@@ -0,0 +1,869 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{
+    assert_err_contains, instantiate_pair, instantiate_pair_of, mock_app, pair_fixture, setup, swap,
+};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, to_vec, Addr, Binary, Decimal, Empty, QueryRequest, Uint128,
+    WasmQuery,
+};
+use cw_terra_test_mocks::prelude::*;
+use cw_terra_test_mocks::terraswap_mock::PoolState;
+use terra_multi_test::{App, AppResponse, Executor};
+use terraswap::asset::Asset;
+use terraswap::pair::SimulationResponse;
+
+#[test]
+fn pair_query_without_asset_infos() {
+    let (_guard, app, pair) = pair_fixture();
+    set_liq_token_addr("lp_token".to_string());
+
+    let pair_info: PairResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::Pair { asset_infos: None })
+        .unwrap();
+    assert_eq!(pair_info, mock_pair_info());
+    assert_eq!(pair_info.liquidity_token, "lp_token");
+}
+
+#[test]
+fn pair_query_with_matching_asset_infos() {
+    let (_guard, app, pair) = pair_fixture();
+
+    let pair_info: PairResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Pair {
+                asset_infos: Some([native("uusd"), native("uusd")]),
+            },
+        )
+        .unwrap();
+    assert_eq!(pair_info, mock_pair_info());
+}
+
+#[test]
+fn pair_query_with_mismatching_asset_infos() {
+    let (_guard, app, pair) = pair_fixture();
+
+    assert_err_contains(
+        app.query_mock::<PairResponse, _>(
+            &pair,
+            &TerraswapQueryMsg::Pair {
+                asset_infos: Some([native("uusd"), native("uluna")]),
+            },
+        ),
+        &MockError::PairMismatch {}.to_string(),
+    );
+}
+
+fn assets(app: &App, pair: &Addr) -> [terraswap::asset::AssetInfo; 2] {
+    let assets: AssetsResponse = app.query_mock(pair, &TerraswapQueryMsg::Assets {}).unwrap();
+    assets.asset_infos
+}
+
+#[test]
+fn assets_query_returns_the_configured_asset_infos() {
+    let (_guard, mut app, default_pair) = pair_fixture();
+    let pair = instantiate_pair_of(&mut app, [token("cw20"), native("uluna")]);
+
+    assert_eq!(
+        assets(&app, &default_pair),
+        [native("token"), native("uusd")]
+    );
+    assert_eq!(assets(&app, &pair), [token("cw20"), native("uluna")]);
+}
+
+#[test]
+fn capabilities_match_the_enabled_features() {
+    let (_guard, app, pair) = pair_fixture();
+
+    let capabilities: CapabilitiesResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::Capabilities {})
+        .unwrap();
+    assert_eq!(capabilities, mock_capabilities());
+    assert_eq!(capabilities.version, env!("CARGO_PKG_VERSION"));
+    assert!(capabilities.terraswap);
+    assert_eq!(capabilities.staking, cfg!(feature = "staking"));
+    assert_eq!(capabilities.cw721, cfg!(feature = "cw721"));
+    assert!(capabilities
+        .terraswap_messages
+        .contains(&"provide_liquidity".to_string()));
+}
+
+#[test]
+fn simulation_both_legs_differ_on_imbalanced_reserves() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let offer_amount = Uint128::new(1_000);
+
+    // 10000 token against 10000 uusd, both legs are the same
+    let both: SimulationBothResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::SimulationBoth { offer_amount })
+        .unwrap();
+    assert_eq!(both.forward, both.reverse);
+
+    swap(&mut app, &pair, "alice", "uusd", 5_000);
+    let both: SimulationBothResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::SimulationBoth { offer_amount })
+        .unwrap();
+    assert_ne!(both.forward, both.reverse);
+    // uusd is now plentiful so it buys less token than token buys uusd
+    assert!(both.forward.return_amount < both.reverse.return_amount);
+
+    // Each leg is the plain simulation of that direction
+    let forward: SimulationResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: asset(native("uusd"), offer_amount),
+            },
+        )
+        .unwrap();
+    let reverse: SimulationResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: asset(native("token"), offer_amount),
+            },
+        )
+        .unwrap();
+    assert_eq!(both.forward, forward);
+    assert_eq!(both.reverse, reverse);
+}
+
+#[test]
+fn protocol_part_of_the_commission_goes_to_the_treasury() {
+    let (_guard, mut app, pair) = pair_fixture();
+    // 3% commission, two thirds of it to the protocol
+    set_commission_split(100, 200);
+    set_treasury_addr("dao".to_string());
+
+    let mut expected_fees: Vec<Asset> = vec![];
+    for &(denom, ask_denom, amount) in &[
+        ("uusd", "token", 1_000),
+        ("token", "uusd", 1_500),
+        ("uusd", "token", 700),
+    ] {
+        let before: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+        let simulation: SimulationResponse = app
+            .query_mock(
+                &pair,
+                &TerraswapQueryMsg::Simulation {
+                    offer_asset: asset(native(denom), Uint128::new(amount)),
+                },
+            )
+            .unwrap();
+        let protocol_fee = simulation
+            .commission_amount
+            .multiply_ratio(200u128, 300u128);
+        assert!(!protocol_fee.is_zero());
+        match expected_fees
+            .iter_mut()
+            .find(|fee| fee.info == native(ask_denom))
+        {
+            Some(fee) => fee.amount += protocol_fee,
+            None => expected_fees.push(asset(native(ask_denom), protocol_fee)),
+        }
+
+        swap(&mut app, &pair, "alice", denom, amount);
+        let after: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+        let reserve = |pool: &PoolResponse, denom: &str| {
+            pool.assets
+                .iter()
+                .find(|pool_asset| pool_asset.info == native(denom))
+                .unwrap()
+                .amount
+        };
+        assert_eq!(
+            reserve(&after, denom),
+            reserve(&before, denom) + Uint128::new(amount)
+        );
+        // The LP part of the commission stays behind in the pool, the protocol part leaves it
+        assert_eq!(
+            reserve(&after, ask_denom),
+            reserve(&before, ask_denom) - simulation.return_amount - protocol_fee
+        );
+    }
+
+    let treasury: TreasuryResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::Treasury {})
+        .unwrap();
+    assert_eq!(treasury.treasury, "dao");
+    assert_eq!(treasury.fees, expected_fees);
+}
+
+#[test]
+fn paid_swap_sends_the_protocol_fee_to_the_treasury() {
+    let (_guard, mut app, pair) = pair_fixture();
+    set_commission_split(100, 200);
+    set_treasury_addr("dao".to_string());
+    app.init_bank_balance(&pair, coins(10_000, "token"))
+        .unwrap();
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(1_000, "uusd"))
+        .unwrap();
+
+    let simulation: SimulationResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: asset(native("uusd"), Uint128::new(1_000)),
+            },
+        )
+        .unwrap();
+    let protocol_fee = simulation
+        .commission_amount
+        .multiply_ratio(200u128, 300u128);
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        pair.clone(),
+        &TerraswapExecuteMsg::Swap {
+            offer_asset: asset(native("uusd"), Uint128::new(1_000)),
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+        &coins(1_000, "uusd"),
+    )
+    .unwrap();
+
+    let balance = |account: &str| app.wrap().query_balance(account, "token").unwrap().amount;
+    assert_eq!(balance("dao"), protocol_fee);
+    assert_eq!(balance("alice"), simulation.return_amount);
+    assert_eq!(
+        balance(pair.as_str()),
+        Uint128::new(10_000) - simulation.return_amount - protocol_fee
+    );
+}
+
+fn query_price_impact(app: &App, pair: &Addr, denom: &str, amount: u128) -> Decimal {
+    let impact: PriceImpactResponse = app
+        .query_mock(
+            pair,
+            &TerraswapQueryMsg::PriceImpact {
+                offer_asset: asset(native(denom), Uint128::new(amount)),
+            },
+        )
+        .unwrap();
+    impact.price_impact
+}
+
+#[test]
+fn price_impact_of_a_swap() {
+    let (_guard, app, pair) = pair_fixture();
+
+    // 1000uusd returns 907 token, leaving 11000uusd against 9093 token, the price drops from 1 to 0.8266
+    assert_eq!(
+        query_price_impact(&app, &pair, "uusd", 1_000),
+        Decimal::from_ratio(1_734u128, 10_000u128)
+    );
+    // Bigger offers move the price more
+    assert!(
+        query_price_impact(&app, &pair, "uusd", 5_000)
+            > query_price_impact(&app, &pair, "uusd", 1_000)
+    );
+    // The query doesn't move the pool
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+}
+
+#[test]
+fn price_impact_of_a_dust_offer() {
+    let (_guard, mut app, pair) = pair_fixture();
+
+    // Swapping 1uusd returns nothing and is rejected
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            pair.clone(),
+            &TerraswapExecuteMsg::Swap {
+                offer_asset: asset(native("uusd"), Uint128::new(1)),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[],
+        ),
+        &MockError::ZeroReturnAmount {}.to_string(),
+    );
+    // Its impact can still be asked for, the 1uusd would sit in the pool for nothing
+    assert_eq!(
+        query_price_impact(&app, &pair, "uusd", 1),
+        Decimal::from_ratio(1u128, 10_000u128)
+    );
+}
+
+#[test]
+fn forced_swap_overflow() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let simulation = TerraswapQueryMsg::Simulation {
+        offer_asset: asset(native("uusd"), Uint128::new(100)),
+    };
+
+    set_force_swap_overflow(true);
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            pair.clone(),
+            &TerraswapExecuteMsg::Swap {
+                offer_asset: asset(native("uusd"), Uint128::new(100)),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[],
+        ),
+        "Overflow",
+    );
+    assert_err_contains(
+        app.query_mock::<SimulationResponse, _>(&pair, &simulation),
+        "Overflow",
+    );
+    // Only the swap math fails, the pool still reads fine
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+
+    set_force_swap_overflow(false);
+    app.query_mock::<SimulationResponse, _>(&pair, &simulation)
+        .unwrap();
+    swap(&mut app, &pair, "alice", "uusd", 100);
+}
+
+#[test]
+fn scheduled_reserves_apply_from_their_height_to_their_pair_only() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let other_pair = instantiate_pair(&mut app);
+    let pool = |app: &App, pair: &Addr| -> PoolResponse {
+        app.query_mock(pair, &TerraswapQueryMsg::Pool {}).unwrap()
+    };
+    let simulation = TerraswapQueryMsg::Simulation {
+        offer_asset: asset(native("uusd"), Uint128::new(1_000)),
+    };
+
+    let at_height = app.block_info().height + 5;
+    schedule_reserves(
+        pair.to_string(),
+        at_height,
+        asset(native("uusd"), Uint128::new(40_000)),
+        asset(native("token"), Uint128::new(20_000)),
+    );
+    let before: SimulationResponse = app.query_mock(&pair, &simulation).unwrap();
+    advance_blocks(&mut app, 4);
+    assert_eq!(pool(&app, &pair), mock_pool_info());
+    assert_eq!(
+        app.query_mock::<SimulationResponse, _>(&pair, &simulation)
+            .unwrap(),
+        before
+    );
+
+    advance_blocks(&mut app, 1);
+    // The reserves are given in any order and land on the matching pool asset
+    let scheduled = pool(&app, &pair);
+    assert_eq!(scheduled.assets[0].amount, Uint128::new(20_000));
+    assert_eq!(scheduled.assets[1].amount, Uint128::new(40_000));
+    let after: SimulationResponse = app.query_mock(&pair, &simulation).unwrap();
+    assert!(after.return_amount < before.return_amount);
+
+    // The other pair never had a change scheduled
+    assert_eq!(pool(&app, &other_pair), mock_pool_info());
+}
+
+#[test]
+fn scheduled_reserves_survive_a_reverted_execute() {
+    let (_guard, mut app, pair) = pair_fixture();
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(200, "uusd"))
+        .unwrap();
+    let scheduled =
+        |app: &App| -> PoolResponse { app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap() };
+    schedule_reserves(
+        pair.to_string(),
+        app.block_info().height,
+        asset(native("token"), Uint128::new(20_000)),
+        asset(native("uusd"), Uint128::new(40_000)),
+    );
+    let due = scheduled(&app);
+    assert_eq!(due.assets[0].amount, Uint128::new(20_000));
+    let paid_swap = |app: &mut App| {
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            pair.clone(),
+            &TerraswapExecuteMsg::Swap {
+                offer_asset: asset(native("uusd"), Uint128::new(100)),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &coins(100, "uusd"),
+        )
+    };
+
+    // The pair holds no token coins to pay the return with, the whole execute reverts
+    paid_swap(&mut app).unwrap_err();
+    assert_eq!(scheduled(&app), due);
+
+    // Once an execute goes through the change is in the stored pool and isn't applied over it again
+    app.init_bank_balance(&pair, coins(10_000, "token"))
+        .unwrap();
+    paid_swap(&mut app).unwrap();
+    let swapped = scheduled(&app);
+    assert_eq!(swapped.assets[1].amount, Uint128::new(40_100));
+    paid_swap(&mut app).unwrap();
+    assert_eq!(scheduled(&app).assets[1].amount, Uint128::new(40_200));
+}
+
+#[test]
+fn offering_an_unknown_denom_is_a_mismatch() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let pair = instantiate_pair_of(&mut app, [native("uusd"), native("uluna")]);
+    let uatom = asset(native("uatom"), Uint128::new(100));
+    let mismatch = MockError::OfferAssetMismatch {
+        offer: "uatom".to_string(),
+        asset0: "uusd".to_string(),
+        asset1: "uluna".to_string(),
+    }
+    .to_string();
+
+    assert_err_contains(
+        app.query_mock::<SimulationResponse, _>(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: uatom.clone(),
+            },
+        ),
+        &mismatch,
+    );
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            pair.clone(),
+            &TerraswapExecuteMsg::Swap {
+                offer_asset: uatom,
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[],
+        ),
+        &mismatch,
+    );
+}
+
+#[test]
+fn oversized_swap_is_insufficient_liquidity() {
+    let (_guard, mut app, pair) = pair_fixture();
+    // Floor rounding always leaves a unit in the ask pool, rounding the output up lets it reach the whole pool
+    set_rounding(RoundingMode::Ceil);
+    let enormous = asset(
+        native("uusd"),
+        Uint128::new(1_000_000_000_000_000_000_000_000_000),
+    );
+    let insufficient = MockError::InsufficientLiquidity {}.to_string();
+
+    assert_err_contains(
+        app.query_mock::<SimulationResponse, _>(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: enormous.clone(),
+            },
+        ),
+        &insufficient,
+    );
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            pair.clone(),
+            &TerraswapExecuteMsg::Swap {
+                offer_asset: enormous,
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[],
+        ),
+        &insufficient,
+    );
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+}
+
+fn pair_config(app: &App, pair: &Addr) -> TerraswapConfigResponse {
+    app.query_mock(pair, &TerraswapQueryMsg::Config {}).unwrap()
+}
+
+#[test]
+fn sudo_update_config_changes_only_that_pair() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let other_pair = instantiate_pair(&mut app);
+    let simulation = TerraswapQueryMsg::Simulation {
+        offer_asset: asset(native("uusd"), Uint128::new(1_000)),
+    };
+    let before: SimulationResponse = app.query_mock(&pair, &simulation).unwrap();
+
+    app.wasm_sudo(
+        pair.clone(),
+        &TerraswapSudoMsg::UpdateConfig {
+            commission_rate: Decimal::percent(1),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        pair_config(&app, &pair),
+        TerraswapConfigResponse {
+            commission_rate: Decimal::percent(1),
+            commission_split: (100, 0),
+        }
+    );
+    // The 909 the swap returns before commission now pays 1% of it
+    let after: SimulationResponse = app.query_mock(&pair, &simulation).unwrap();
+    assert_eq!(after.commission_amount, Uint128::new(9));
+    assert_eq!(
+        after.return_amount + after.commission_amount,
+        before.return_amount + before.commission_amount
+    );
+
+    // The other pair and the default for new pairs keep the 0.3%
+    assert_eq!(pair_config(&app, &other_pair).commission_split, (30, 0));
+    assert_eq!(
+        app.query_mock::<SimulationResponse, _>(&other_pair, &simulation)
+            .unwrap(),
+        before
+    );
+    assert_eq!(get_commission_split(), (30, 0));
+
+    assert_err_contains(
+        app.wasm_sudo(
+            pair.clone(),
+            &TerraswapSudoMsg::UpdateConfig {
+                commission_rate: Decimal::percent(101),
+            },
+        ),
+        &MockError::InvalidCommissionRate {}.to_string(),
+    );
+    assert_eq!(pair_config(&app, &pair).commission_split, (100, 0));
+}
+
+#[test]
+fn provide_quote_refunds_the_excess_side() {
+    let (_guard, app, pair) = pair_fixture();
+
+    // 10000 token and 10000 uusd back 1000 shares, 1000 of each is worth 100 of them
+    let quote: ProvideQuoteResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::ProvideQuote {
+                deposits: [
+                    asset(native("uusd"), Uint128::new(3_000)),
+                    asset(native("token"), Uint128::new(1_000)),
+                ],
+            },
+        )
+        .unwrap();
+    assert_eq!(quote.share, Uint128::new(100));
+    // Refunds come in pool order, the 2000uusd beyond the pool ratio goes back
+    assert_eq!(
+        quote.refund_assets,
+        [
+            asset(native("token"), Uint128::zero()),
+            asset(native("uusd"), Uint128::new(2_000)),
+        ]
+    );
+    // A quote doesn't provide anything
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+}
+
+#[test]
+fn pair_addresses_follow_the_instantiation_order() {
+    let _guard = setup();
+    // Without instantiate2 the address can't come from a salt, but the App hands out addresses in order so the same
+    // setup always gives the mock the same address and it can be passed to the contract under test
+    let addresses = || {
+        let mut app = mock_app();
+        let first = instantiate_pair(&mut app);
+        let second = instantiate_pair(&mut app);
+        (first, second)
+    };
+    let (first, second) = addresses();
+    assert_ne!(first, second);
+    assert_eq!(addresses(), (first, second));
+}
+
+#[test]
+fn instantiate2_mock_lands_on_the_predicted_address() {
+    let _guard = setup();
+    let mut app = mock_app();
+    // Known before anything is instantiated, so it can go into the instantiate message of the contract under test
+    let predicted = predict_contract_address(1);
+    instantiate_pair(&mut app);
+    let pair = instantiate2_terraswap_mock(
+        &mut app,
+        &Addr::unchecked("owner"),
+        Some("admin".to_string()),
+        1,
+    );
+    assert_eq!(pair, predicted);
+    assert_eq!(
+        app.query_mock::<PoolResponse, _>(&pair, &TerraswapQueryMsg::Pool {})
+            .unwrap(),
+        mock_pool_info()
+    );
+}
+
+fn volume(app: &App, pair: &Addr) -> VolumeResponse {
+    app.query_mock(pair, &TerraswapQueryMsg::Volume {}).unwrap()
+}
+
+#[test]
+fn volume_adds_up_every_swap() {
+    let (_guard, mut app, pair) = pair_fixture();
+    assert_eq!(
+        volume(&app, &pair),
+        VolumeResponse {
+            offer_volume: vec![],
+            ask_volume: vec![],
+        }
+    );
+
+    let first = swap(&mut app, &pair, "alice", "uusd", 1_000);
+    let second = swap(&mut app, &pair, "bob", "uusd", 500);
+    let third = swap(&mut app, &pair, "carol", "token", 200);
+    let returned = |res: &AppResponse| -> Uint128 {
+        attribute_value(res, "return_amount")
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+    assert_eq!(
+        volume(&app, &pair),
+        VolumeResponse {
+            offer_volume: vec![
+                asset(native("uusd"), Uint128::new(1_500)),
+                asset(native("token"), Uint128::new(200)),
+            ],
+            ask_volume: vec![
+                asset(native("token"), returned(&first) + returned(&second)),
+                asset(native("uusd"), returned(&third)),
+            ],
+        }
+    );
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        pair.clone(),
+        &TerraswapExecuteMsg::ResetVolume {},
+        &[],
+    )
+    .unwrap();
+    assert!(volume(&app, &pair).offer_volume.is_empty());
+}
+
+fn swap_dust(app: &mut App, pair: &Addr) -> Result<AppResponse, String> {
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        pair.clone(),
+        &TerraswapExecuteMsg::Swap {
+            offer_asset: asset(native("uusd"), Uint128::new(1)),
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+        &coins(1, "uusd"),
+    )
+    .map_err(|err| err.to_string())
+}
+
+#[test]
+fn swap_returning_zero_is_rejected_unless_allowed() {
+    let (_guard, mut app, pair) = pair_fixture();
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(2, "uusd"))
+        .unwrap();
+
+    assert_err_contains(
+        swap_dust(&mut app, &pair),
+        &MockError::ZeroReturnAmount {}.to_string(),
+    );
+    // Nothing moved, the offered coin went back with the reverted execute
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+    let uusd = app.wrap().query_balance("alice", "uusd").unwrap();
+    assert_eq!(uusd.amount.u128(), 2);
+
+    // Allowed, the dust goes into the pool for nothing
+    set_reject_zero_return(false);
+    let res = swap_dust(&mut app, &pair).unwrap();
+    assert_attribute(&res, "return_amount", "0");
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool.assets[1].amount.u128(), 10_001);
+    assert_eq!(pool.assets[0].amount, mock_pool_info().assets[0].amount);
+    let uusd = app.wrap().query_balance("alice", "uusd").unwrap();
+    assert_eq!(uusd.amount.u128(), 1);
+}
+
+fn k_history(app: &App, pair: &Addr, start_after: Option<u64>, limit: Option<u32>) -> Vec<KEntry> {
+    let history: KHistoryResponse = app
+        .query_mock(pair, &TerraswapQueryMsg::KHistory { start_after, limit })
+        .unwrap();
+    history.entries
+}
+
+#[test]
+fn k_never_goes_down_across_swaps() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let start = mock_pool_info();
+    let start_k = pool_k(&PoolState {
+        assets: start.assets,
+        total_share: start.total_share.into(),
+    });
+
+    for (trader, denom, amount) in [
+        ("alice", "uusd", 1_000),
+        ("bob", "token", 2_500),
+        ("carol", "uusd", 10),
+        ("dave", "token", 700),
+        ("erin", "uusd", 4_000),
+    ]
+    .iter()
+    {
+        swap(&mut app, &pair, trader, denom, *amount);
+    }
+
+    let entries = k_history(&app, &pair, None, None);
+    assert_eq!(
+        entries.iter().map(|entry| entry.seq).collect::<Vec<u64>>(),
+        vec![1, 2, 3, 4, 5]
+    );
+    assert!(entries[0].k >= start_k);
+    for window in entries.windows(2) {
+        assert!(window[1].k >= window[0].k, "k went down: {:?}", window);
+    }
+
+    // Paged by sequence number
+    let page = k_history(&app, &pair, Some(2), Some(2));
+    assert_eq!(page, entries[2..4].to_vec());
+}
+
+fn pool(app: &App, pair: &Addr) -> PoolResponse {
+    app.query_mock(pair, &TerraswapQueryMsg::Pool {}).unwrap()
+}
+
+#[test]
+fn lagged_pool_shows_a_swap_only_after_the_lag() {
+    let (_guard, mut app, pair) = pair_fixture();
+    // The swap goes in a later block than the instantiation, a block only keeps the last state it ends with
+    advance_blocks(&mut app, 1);
+    swap(&mut app, &pair, "alice", "uusd", 1_000);
+    let swapped = pool(&app, &pair);
+    assert_ne!(swapped, mock_pool_info());
+
+    set_query_lag(2);
+    assert_eq!(pool(&app, &pair), mock_pool_info());
+    advance_blocks(&mut app, 1);
+    assert_eq!(pool(&app, &pair), mock_pool_info());
+    advance_blocks(&mut app, 1);
+    assert_eq!(pool(&app, &pair), swapped);
+
+    set_query_lag(0);
+    assert_eq!(pool(&app, &pair), swapped);
+}
+
+#[test]
+fn pool_query_never_answers_with_an_empty_binary() {
+    let (_guard, mut app, pair) = pair_fixture();
+    swap(&mut app, &pair, "alice", "uusd", 1_000);
+
+    // The bytes as the pair returned them, before any decoding could paper over an empty answer
+    let request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair.to_string(),
+        msg: to_binary(&TerraswapQueryMsg::Pool {}).unwrap(),
+    });
+    let raw: Binary = app
+        .wrap()
+        .raw_query(&to_vec(&request).unwrap())
+        .into_result()
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert!(!raw.is_empty());
+    assert_ne!(raw, Binary::from(b"{}".as_ref()));
+    assert_ne!(raw, Binary::from(b"null".as_ref()));
+
+    let decoded: PoolResponse = from_binary(&raw).unwrap();
+    assert_eq!(decoded, pool(&app, &pair));
+    assert_ne!(decoded.assets[0].amount, Uint128::zero());
+    assert_ne!(decoded.total_share, Uint128::zero());
+}
+
+#[test]
+fn alternating_swaps_grow_k_by_the_kept_commission() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let k = |pool: &PoolResponse| pool.assets[0].amount.u128() * pool.assets[1].amount.u128();
+    let amount = |res: &AppResponse, key: &str| -> u128 {
+        attribute_value(res, key).unwrap().parse().unwrap()
+    };
+
+    for (trader, denom, offer) in [
+        ("alice", "uusd", 1_000),
+        ("bob", "token", 1_500),
+        ("carol", "uusd", 700),
+        ("dave", "token", 800),
+        ("erin", "uusd", 2_000),
+        ("frank", "token", 600),
+    ]
+    .iter()
+    {
+        let before = pool(&app, &pair);
+        let res = swap(&mut app, &pair, trader, denom, *offer);
+        let after = pool(&app, &pair);
+        assert_k_preserved(&before, &after);
+
+        // Without the commission the floored return alone keeps k, the part of the commission left in the pool
+        // grows it by at least that amount times the offer reserve
+        let kept = amount(&res, "commission_amount") - amount(&res, "protocol_fee_amount");
+        assert!(kept > 0, "swap of {} {} kept no commission", offer, denom);
+        let offer_reserve = after
+            .assets
+            .iter()
+            .find(|reserve| reserve.info == native(denom))
+            .unwrap()
+            .amount
+            .u128();
+        assert!(
+            k(&after) >= k(&before) + offer_reserve * kept,
+            "k grew by {} for a kept commission of {}",
+            k(&after) - k(&before),
+            kept
+        );
+    }
+}
+
+#[test]
+fn exchange_rate_is_the_reserve_ratio_less_the_commission() {
+    let (_guard, mut app, pair) = pair_fixture();
+    swap(&mut app, &pair, "alice", "uusd", 2_500);
+    let pool: PoolResponse = app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    let epsilon = Decimal::from_ratio(1u128, 1_000_000_000_000_000u128);
+
+    for offer in 0..2 {
+        let offer_reserve = pool.assets[offer].amount;
+        let ask_reserve = pool.assets[1 - offer].amount;
+        let rate: ExchangeRateResponse = app
+            .query_mock(
+                &pair,
+                &TerraswapQueryMsg::ExchangeRate {
+                    offer_asset_info: pool.assets[offer].info.clone(),
+                },
+            )
+            .unwrap();
+        // Worse than the bare reserve ratio, by the 0.3% commission and nothing more
+        let reserve_ratio = Decimal::from_ratio(ask_reserve, offer_reserve);
+        let expected = Decimal::from_ratio(ask_reserve.u128() * 997, offer_reserve.u128() * 1_000);
+        assert!(rate.exchange_rate < reserve_ratio);
+        assert!(rate.exchange_rate <= expected);
+        assert!(
+            rate.exchange_rate + epsilon >= expected,
+            "{} is more than the commission below {}",
+            rate.exchange_rate,
+            reserve_ratio
+        );
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{mock_app, setup, OWNER};
+use cosmwasm_std::Addr;
+use terra_multi_test::Executor;
+
+#[test]
+fn prelude_and_module_paths_name_the_same_items() {
+    let _guard = setup();
+    use cw_terra_test_mocks::prelude::*;
+    use cw_terra_test_mocks::terraswap_mock;
+
+    let mut app = mock_app();
+    // Stored through the module path, instantiated and queried with the prelude names
+    let code_id = app.store_code(terraswap_mock::contract_terraswap_mock());
+    let pair = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &TerraswapInstantiateMsg::default(),
+            &[],
+            "pair",
+            None,
+        )
+        .unwrap();
+    let pool: terraswap_mock::PoolResponse =
+        app.query_mock(&pair, &TerraswapQueryMsg::Pool {}).unwrap();
+    assert_eq!(pool, mock_pool_info());
+
+    let msg: terraswap_mock::MockQueryMsg = TerraswapQueryMsg::Pool {};
+    assert_eq!(msg.kind(), TerraswapQueryMsgKind::Pool);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{assert_err_contains, setup};
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use cosmwasm_std::{coin, coins, Addr, Uint128};
+use cw20::TokenInfoResponse;
+use cw_terra_test_mocks::prelude::*;
+use cw_terra_test_mocks::terraswap_mock::PoolState;
+use terraswap::pair::SimulationResponse;
+
+#[test]
+fn smart_queries_get_the_mock_responses() {
+    let _guard = setup();
+    let deps = mock_dependencies_with_terraswap(&[]);
+    let querier = deps.as_ref().querier;
+
+    let pool: PoolResponse = querier
+        .query_wasm_smart("any_pair", &TerraswapQueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(pool, mock_pool_info());
+    let pair: PairResponse = querier
+        .query_wasm_smart("any_pair", &TerraswapQueryMsg::Pair { asset_infos: None })
+        .unwrap();
+    assert_eq!(pair, mock_pair_info());
+    let token_info: TokenInfoResponse = querier
+        .query_wasm_smart("any_token", &TerraswapQueryMsg::TokenInfo {})
+        .unwrap();
+    assert_eq!(token_info, mock_token_info());
+
+    // Simulation runs over the dummy pool
+    let offer_asset = asset(native("token"), Uint128::new(1_000));
+    let simulation: SimulationResponse = querier
+        .query_wasm_smart(
+            "any_pair",
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+            },
+        )
+        .unwrap();
+    let expected = simulate_swap(
+        &PoolState {
+            assets: mock_pool_info().assets,
+            total_share: mock_pool_info().total_share.into(),
+        },
+        &offer_asset,
+    )
+    .unwrap();
+    assert_eq!(simulation, expected);
+}
+
+#[test]
+fn queries_needing_storage_are_unsupported() {
+    let _guard = setup();
+    let deps = mock_dependencies_with_terraswap(&[]);
+    assert_err_contains(
+        deps.as_ref()
+            .querier
+            .query_wasm_smart::<VolumeResponse, _, _>("any_pair", &TerraswapQueryMsg::Volume {}),
+        &MockError::UnsupportedQuery {
+            kind: "Volume".to_string(),
+        }
+        .to_string(),
+    );
+}
+
+#[test]
+fn bank_queries_fall_through_to_the_mock_querier() {
+    let _guard = setup();
+    let deps = mock_dependencies_with_terraswap(&coins(500, "uusd"));
+    let balance = deps
+        .as_ref()
+        .querier
+        .query_balance(Addr::unchecked(MOCK_CONTRACT_ADDR), "uusd")
+        .unwrap();
+    assert_eq!(balance.amount.u128(), 500);
+}
+
+#[test]
+fn native_balances_set_on_the_querier() {
+    let _guard = setup();
+    let deps = mock_dependencies_with_terraswap(&[]);
+    set_querier_native_balance(
+        "alice".to_string(),
+        vec![coin(1_000, "uusd"), coin(5, "uluna")],
+    );
+    let querier = deps.as_ref().querier;
+
+    assert_eq!(
+        querier.query_balance("alice", "uusd").unwrap(),
+        coin(1_000, "uusd")
+    );
+    // A denom alice doesn't hold is a zero balance
+    assert_eq!(
+        querier.query_balance("alice", "ukrw").unwrap(),
+        coin(0, "ukrw")
+    );
+    assert_eq!(
+        querier.query_all_balances("alice").unwrap(),
+        vec![coin(1_000, "uusd"), coin(5, "uluna")]
+    );
+    // Accounts without a balance set fall through to the MockQuerier, which has none for them
+    assert!(querier.query_all_balances("bob").unwrap().is_empty());
+
+    clear_querier_native_balances();
+    assert_eq!(
+        querier.query_balance("alice", "uusd").unwrap(),
+        coin(0, "uusd")
+    );
+}
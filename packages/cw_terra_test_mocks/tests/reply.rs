@@ -0,0 +1,189 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{
+    instantiate_caller, pair_fixture, CallerExecuteMsg, CallerQueryMsg, LastReply, OWNER,
+};
+use cosmwasm_std::{from_binary, to_binary, Addr, Binary, ReplyOn, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, AppResponse, Executor};
+use terraswap::pair::SimulationResponse;
+
+#[test]
+fn swap_reply_carries_the_swap_response() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+    let offer_asset = asset(native("uusd"), Uint128::new(1_000));
+    let simulation: SimulationResponse = app
+        .query_mock(
+            &pair,
+            &TerraswapQueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+            },
+        )
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        caller.clone(),
+        &CallerExecuteMsg::ForwardWithReply {
+            contract: pair.to_string(),
+            msg: to_binary(&TerraswapExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })
+            .unwrap(),
+            id: 7,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let last_reply: LastReply = app
+        .query_mock(&caller, &CallerQueryMsg::LastReply {})
+        .unwrap();
+    let (id, data) = last_reply.expect("the caller got no reply");
+    assert_eq!(id, 7);
+    let swap: SwapResponse = from_binary(&data.expect("the reply has no data")).unwrap();
+    assert_eq!(
+        swap,
+        SwapResponse {
+            offer_asset,
+            return_asset: asset(native("token"), simulation.return_amount),
+            spread_amount: simulation.spread_amount,
+            commission_amount: simulation.commission_amount,
+        }
+    );
+}
+
+// Mint `amount` to alice and have her Send it to the caller with a payload, so the receive hook is dispatched
+fn send_to_caller(app: &mut App, token: &Addr, caller: &Addr, amount: u128) -> AppResponse {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &TerraswapExecuteMsg::Mint {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        token.clone(),
+        &TerraswapExecuteMsg::Send {
+            contract: caller.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&"deposit").unwrap(),
+        },
+        &[],
+    )
+    .unwrap()
+}
+
+// The reply_id of the reply the token handled, None if it got no reply
+fn token_reply_id(res: &AppResponse, token: &Addr) -> Option<u64> {
+    res.events
+        .iter()
+        .filter(|event| {
+            event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "_contract_addr" && attr.value == token.as_str())
+        })
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "reply_id")
+        .map(|attr| attr.value.parse().unwrap())
+}
+
+#[test]
+fn send_is_fire_and_forget_by_default() {
+    let (_guard, mut app, token) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+
+    let res = send_to_caller(&mut app, &token, &caller, 100);
+    assert_eq!(token_reply_id(&res, &token), None);
+    let received: Vec<Cw20ReceiveMsg> = app
+        .query_mock(&caller, &CallerQueryMsg::Received {})
+        .unwrap();
+    assert_eq!(received.len(), 1);
+}
+
+#[test]
+fn send_replies_on_success_when_asked() {
+    let (_guard, mut app, token) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+
+    set_send_reply_on(ReplyOn::Success);
+    let res = send_to_caller(&mut app, &token, &caller, 100);
+    assert_eq!(token_reply_id(&res, &token), Some(SEND_REPLY_ID));
+    // The hook itself ran as before
+    let received: Vec<Cw20ReceiveMsg> = app
+        .query_mock(&caller, &CallerQueryMsg::Received {})
+        .unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].amount, Uint128::new(100));
+}
+
+#[test]
+fn send_dispatches_with_the_configured_reply_id() {
+    let (_guard, mut app, token) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+    // Each operation replies with its own id
+    assert_ne!(SEND_REPLY_ID, SWAP_REPLY_ID);
+    assert_eq!(get_reply_id(), SEND_REPLY_ID);
+
+    set_send_reply_on(ReplyOn::Always);
+    set_reply_id(42);
+    let res = send_to_caller(&mut app, &token, &caller, 100);
+    assert_eq!(token_reply_id(&res, &token), Some(42));
+}
+
+// Forward a ping hook straight to the pair's Receive and return the data of the pong the caller got replied
+fn pong_data(app: &mut App, caller: &Addr, pair: &Addr, ping: &Binary) -> Binary {
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        caller.clone(),
+        &CallerExecuteMsg::ForwardWithReply {
+            contract: pair.to_string(),
+            msg: to_binary(&TerraswapExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "alice".to_string(),
+                amount: Uint128::new(10),
+                msg: ping.clone(),
+            }))
+            .unwrap(),
+            id: 1,
+        },
+        &[],
+    )
+    .unwrap();
+    let last_reply: LastReply = app
+        .query_mock(caller, &CallerQueryMsg::LastReply {})
+        .unwrap();
+    let (_, data) = last_reply.expect("the caller got no reply");
+    data.expect("the pong has no data")
+}
+
+#[test]
+fn pong_data_as_a_json_string_or_the_raw_bytes() {
+    let (_guard, mut app, pair) = pair_fixture();
+    let caller = instantiate_caller(&mut app);
+    let ping = to_binary(&PingMsg {
+        payload: "hello".to_string(),
+    })
+    .unwrap();
+
+    // By default the payload comes back JSON encoded, quotes and all
+    let data = pong_data(&mut app, &caller, &pair, &ping);
+    assert_eq!(data, Binary::from(br#""hello""#.to_vec()));
+    assert_eq!(from_binary::<String>(&data).unwrap(), "hello");
+
+    // Raw mode echoes the received hook byte for byte
+    set_pong_raw_bytes(true);
+    let data = pong_data(&mut app, &caller, &pair, &ping);
+    assert_eq!(data, ping);
+}
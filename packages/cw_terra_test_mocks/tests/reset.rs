@@ -0,0 +1,72 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{instantiate_pair, mock_app, setup};
+use cosmwasm_std::coins;
+use cw_terra_test_mocks::prelude::*;
+
+// The liquidity token a freshly instantiated pair reports, like the start of a test that never set one
+fn liquidity_token_of_a_new_pair() -> String {
+    let mut app = mock_app();
+    let pair = instantiate_pair(&mut app);
+    let pair_info: PairResponse = app
+        .query_mock(&pair, &TerraswapQueryMsg::Pair { asset_infos: None })
+        .unwrap();
+    pair_info.liquidity_token
+}
+
+#[test]
+fn liquidity_token_address_does_not_bleed_into_the_next_test() {
+    let _guard = setup();
+    let default = liquidity_token_of_a_new_pair();
+
+    // A test that sets the address
+    {
+        let _mock = MockGuard::new();
+        set_liq_token_addr("lp_of_the_first_test".to_string());
+        assert_eq!(liquidity_token_of_a_new_pair(), "lp_of_the_first_test");
+    }
+    // The test after it never sets one and gets the default back
+    {
+        let _mock = MockGuard::new();
+        assert_eq!(liquidity_token_of_a_new_pair(), default);
+    }
+}
+
+#[test]
+fn a_panicking_test_still_resets() {
+    let _guard = setup();
+    let result = std::panic::catch_unwind(|| {
+        let _mock = MockGuard::new();
+        set_liq_token_addr("lp_of_the_failed_test".to_string());
+        panic!("the test failed");
+    });
+    assert!(result.is_err());
+    assert_ne!(get_liq_token_addr(), "lp_of_the_failed_test");
+
+    // reset_mock_state on its own does the same
+    set_liq_token_addr("lp_set_by_hand".to_string());
+    reset_mock_state();
+    assert_ne!(get_liq_token_addr(), "lp_set_by_hand");
+}
+
+#[test]
+fn dropping_the_guard_restores_every_default() {
+    let _guard = setup();
+    {
+        let _mock = MockGuard::new();
+        set_commission_split(100, 50);
+        set_fail_next_queries(3);
+        set_strict_address_validation(true);
+        set_reject_zero_return(false);
+        set_querier_native_balance("alice".to_string(), coins(5, "uusd"));
+        assert_eq!(get_commission_split(), (100, 50));
+        assert_eq!(get_fail_next_queries(), 3);
+    }
+    assert_eq!(get_commission_split(), (30, 0));
+    assert_eq!(get_fail_next_queries(), 0);
+    assert!(!get_strict_address_validation());
+    assert!(get_reject_zero_return());
+    assert_eq!(get_querier_native_balance("alice"), None);
+}
@@ -0,0 +1,257 @@
+#![cfg(feature = "router")]
+
+mod common;
+
+use common::{
+    assert_err_contains, instantiate, instantiate_pair_of, mock_app, pair_fixture, setup, OWNER,
+};
+use cosmwasm_std::{coins, from_binary, to_binary, Addr, Binary, Coin, Uint128};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+use terraswap::asset::AssetInfo;
+use terraswap::router::SimulateSwapOperationsResponse;
+
+fn hop(offer: AssetInfo, ask: AssetInfo) -> SwapOperation {
+    SwapOperation::TerraSwap {
+        offer_asset_info: offer,
+        ask_asset_info: ask,
+    }
+}
+
+fn luna_to_krw() -> Vec<SwapOperation> {
+    vec![
+        hop(native("uluna"), native("uusd")),
+        hop(native("uusd"), native("ukrw")),
+    ]
+}
+
+// A router over uluna/uusd and uusd/ukrw pairs, each funded with the coins it pays out. alice holds 1000uluna.
+fn setup_route(app: &mut App) -> Addr {
+    let luna_ust = instantiate_pair_of(app, [native("uluna"), native("uusd")]);
+    let ust_krw = instantiate_pair_of(app, [native("uusd"), native("ukrw")]);
+    app.init_bank_balance(&luna_ust, coins(10_000, "uusd"))
+        .unwrap();
+    app.init_bank_balance(&ust_krw, coins(10_000, "ukrw"))
+        .unwrap();
+    app.init_bank_balance(&Addr::unchecked("alice"), coins(1_000, "uluna"))
+        .unwrap();
+    instantiate(
+        app,
+        contract_router_mock(),
+        &RouterInstantiateMsg {
+            pairs: vec![luna_ust.to_string(), ust_krw.to_string()],
+        },
+    )
+}
+
+fn simulate(app: &App, router: &Addr, offer_amount: u128, operations: Vec<SwapOperation>) -> u128 {
+    let simulation: SimulateSwapOperationsResponse = app
+        .query_mock(
+            router,
+            &RouterQueryMsg::SimulateSwapOperations {
+                offer_amount: Uint128::new(offer_amount),
+                operations,
+            },
+        )
+        .unwrap();
+    simulation.amount.u128()
+}
+
+fn execute_route(
+    app: &mut App,
+    router: &Addr,
+    minimum_receive: Option<u128>,
+    funds: &[Coin],
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        router.clone(),
+        &RouterExecuteMsg::ExecuteSwapOperations {
+            operations: luna_to_krw(),
+            minimum_receive: minimum_receive.map(Uint128::new),
+            to: None,
+        },
+        funds,
+    )
+    .map(|_| ())
+    .map_err(|err| err.to_string())
+}
+
+fn balance(app: &App, account: &str, denom: &str) -> u128 {
+    app.wrap()
+        .query_balance(account, denom)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn multi_hop_swap_pays_the_simulated_amount() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let router = setup_route(&mut app);
+
+    let expected = simulate(&app, &router, 100, luna_to_krw());
+    assert!(expected > 0);
+    execute_route(&mut app, &router, Some(expected), &coins(100, "uluna")).unwrap();
+
+    assert_eq!(balance(&app, "alice", "ukrw"), expected);
+    assert_eq!(balance(&app, "alice", "uluna"), 900);
+    // The intermediate uusd went straight on to the second pair
+    assert_eq!(balance(&app, router.as_str(), "uusd"), 0);
+    assert_eq!(balance(&app, router.as_str(), "uluna"), 0);
+}
+
+#[test]
+fn minimum_receive_above_the_return_reverts() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let router = setup_route(&mut app);
+
+    let expected = simulate(&app, &router, 100, luna_to_krw());
+    assert_err_contains(
+        execute_route(&mut app, &router, Some(expected + 1), &coins(100, "uluna")),
+        "minimum receive amount",
+    );
+    assert_eq!(balance(&app, "alice", "uluna"), 1_000);
+    assert_eq!(balance(&app, "alice", "ukrw"), 0);
+}
+
+#[test]
+fn offer_funds_have_to_match_the_first_operation() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let router = setup_route(&mut app);
+    app.init_bank_balance(
+        &Addr::unchecked("alice"),
+        vec![Coin::new(1_000, "uluna"), Coin::new(1_000, "uusd")],
+    )
+    .unwrap();
+
+    assert_err_contains(
+        execute_route(&mut app, &router, None, &[]),
+        &MockError::NoOfferFunds {}.to_string(),
+    );
+    assert_err_contains(
+        execute_route(&mut app, &router, None, &coins(100, "uusd")),
+        &MockError::OfferDenomMismatch {
+            expected: "uluna".to_string(),
+            received: "uusd".to_string(),
+        }
+        .to_string(),
+    );
+}
+
+#[test]
+fn only_the_router_executes_single_operations() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let router = setup_route(&mut app);
+
+    let res = app.execute_contract(
+        Addr::unchecked("alice"),
+        router.clone(),
+        &RouterExecuteMsg::ExecuteSwapOperation {
+            operation: hop(native("uluna"), native("uusd")),
+            to: None,
+        },
+        &[],
+    );
+    assert_err_contains(res, &MockError::Unauthorized {}.to_string());
+}
+
+#[test]
+fn cw20_offer_is_sent_with_the_hook() {
+    let (_guard, mut app, token) = pair_fixture();
+    let token_ust = instantiate_pair_of(&mut app, [token_info(&token), native("uusd")]);
+    app.init_bank_balance(&token_ust, coins(10_000, "uusd"))
+        .unwrap();
+    let router = instantiate(
+        &mut app,
+        contract_router_mock(),
+        &RouterInstantiateMsg {
+            pairs: vec![token_ust.to_string()],
+        },
+    );
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &TerraswapExecuteMsg::Mint {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(1_000),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let operations = vec![hop(token_info(&token), native("uusd"))];
+    let expected = simulate(&app, &router, 100, operations.clone());
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        token.clone(),
+        &TerraswapExecuteMsg::Send {
+            contract: router.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: Some(Uint128::new(expected)),
+                to: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, "alice", "uusd"), expected);
+}
+
+fn token_info(token: &Addr) -> AssetInfo {
+    cw_terra_test_mocks::prelude::token(token.as_str())
+}
+
+#[test]
+fn swap_operations_keep_the_router_wire_format() {
+    let _guard = setup();
+    let native_hop = SwapOperation::NativeSwap {
+        offer_denom: "uluna".to_string(),
+        ask_denom: "uusd".to_string(),
+    };
+    let token_hop = hop(native("uusd"), token("token0000"));
+
+    // The JSON Terraswap's router schema takes
+    assert_eq!(
+        to_binary(&native_hop).unwrap(),
+        Binary::from(br#"{"native_swap":{"offer_denom":"uluna","ask_denom":"uusd"}}"#.to_vec())
+    );
+    assert_eq!(
+        to_binary(&token_hop).unwrap(),
+        Binary::from(
+            br#"{"terra_swap":{"offer_asset_info":{"native_token":{"denom":"uusd"}},"ask_asset_info":{"token":{"contract_addr":"token0000"}}}}"#
+                .to_vec()
+        )
+    );
+
+    // A message built with the production router types is accepted as it is, and the mock's serializes the same
+    let production = to_binary(&terraswap::router::ExecuteMsg::ExecuteSwapOperations {
+        operations: vec![
+            terraswap::router::SwapOperation::NativeSwap {
+                offer_denom: "uluna".to_string(),
+                ask_denom: "uusd".to_string(),
+            },
+            terraswap::router::SwapOperation::TerraSwap {
+                offer_asset_info: native("uusd"),
+                ask_asset_info: token("token0000"),
+            },
+        ],
+        minimum_receive: Some(Uint128::new(10)),
+        to: None,
+    })
+    .unwrap();
+    let mock = RouterExecuteMsg::ExecuteSwapOperations {
+        operations: vec![native_hop, token_hop],
+        minimum_receive: Some(Uint128::new(10)),
+        to: None,
+    };
+    assert_eq!(from_binary::<RouterExecuteMsg>(&production).unwrap(), mock);
+    assert_eq!(to_binary(&mock).unwrap(), production);
+}
@@ -0,0 +1,272 @@
+#![cfg(feature = "staking")]
+
+mod common;
+
+use common::{assert_err_contains, instantiate, instantiate_pair, mock_app, setup, OWNER};
+use cosmwasm_std::{to_binary, Addr, Decimal, Uint128};
+use cw20::BalanceResponse;
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+struct Farm {
+    staking: Addr,
+    lp_token: Addr,
+    reward_token: Addr,
+}
+
+// A staking contract for an LP token, with a terraswap mock standing in for each of the two cw20s.
+// The stakers get LP tokens to bond and the staking contract holds the rewards it pays out.
+fn setup_farm(app: &mut App, stakers: &[(&str, u128)]) -> Farm {
+    let lp_token = instantiate_pair(app);
+    let reward_token = instantiate_pair(app);
+    let staking = instantiate(
+        app,
+        contract_staking_mock(),
+        &StakingInstantiateMsg {
+            staking_token: lp_token.to_string(),
+            reward_token: reward_token.to_string(),
+        },
+    );
+    for (staker, amount) in stakers {
+        mint(app, &lp_token, staker, *amount);
+    }
+    mint(app, &reward_token, staking.as_str(), 1_000_000_000);
+    Farm {
+        staking,
+        lp_token,
+        reward_token,
+    }
+}
+
+fn mint(app: &mut App, token: &Addr, recipient: &str, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &TerraswapExecuteMsg::Mint {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn bond(app: &mut App, farm: &Farm, staker: &str, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(staker),
+        farm.lp_token.clone(),
+        &TerraswapExecuteMsg::Send {
+            contract: farm.staking.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&StakingHookMsg::Bond {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn balance(app: &App, token: &Addr, account: &str) -> u128 {
+    let balance: BalanceResponse = app
+        .query_mock(
+            token,
+            &TerraswapQueryMsg::Balance {
+                address: account.to_string(),
+            },
+        )
+        .unwrap();
+    balance.balance.u128()
+}
+
+fn staker_info(app: &App, farm: &Farm, staker: &str) -> StakerInfoResponse {
+    app.query_mock(
+        &farm.staking,
+        &StakingQueryMsg::StakerInfo {
+            staker: staker.to_string(),
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn bond_and_unbond_move_the_lp_tokens() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let farm = setup_farm(&mut app, &[("alice", 1_000)]);
+
+    bond(&mut app, &farm, "alice", 600);
+    assert_eq!(staker_info(&app, &farm, "alice").bond_amount.u128(), 600);
+    assert_eq!(balance(&app, &farm.lp_token, "alice"), 400);
+    assert_eq!(balance(&app, &farm.lp_token, farm.staking.as_str()), 600);
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        farm.staking.clone(),
+        &StakingExecuteMsg::Unbond {
+            amount: Uint128::new(200),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(staker_info(&app, &farm, "alice").bond_amount.u128(), 400);
+    assert_eq!(balance(&app, &farm.lp_token, "alice"), 600);
+
+    // Can't unbond more than is bonded
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        farm.staking.clone(),
+        &StakingExecuteMsg::Unbond {
+            amount: Uint128::new(401),
+        },
+        &[],
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn only_the_staking_token_can_be_bonded() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let farm = setup_farm(&mut app, &[]);
+    mint(&mut app, &farm.reward_token, "alice", 100);
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        farm.reward_token.clone(),
+        &TerraswapExecuteMsg::Send {
+            contract: farm.staking.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&StakingHookMsg::Bond {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap_err();
+    assert_eq!(staker_info(&app, &farm, "alice").bond_amount.u128(), 0);
+}
+
+#[test]
+fn rewards_accrue_per_block_by_share() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let farm = setup_farm(&mut app, &[("alice", 300), ("bob", 100)]);
+    set_reward_per_block(Uint128::new(1_000));
+
+    bond(&mut app, &farm, "alice", 300);
+    bond(&mut app, &farm, "bob", 100);
+    advance_blocks(&mut app, 10);
+
+    // blocks * rate * share_fraction
+    let alice_reward = 10 * 1_000 * 300 / 400;
+    let bob_reward = 10 * 1_000 * 100 / 400;
+    assert_eq!(
+        staker_info(&app, &farm, "alice").pending_reward.u128(),
+        alice_reward
+    );
+    assert_eq!(
+        staker_info(&app, &farm, "bob").pending_reward.u128(),
+        bob_reward
+    );
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        farm.staking.clone(),
+        &StakingExecuteMsg::Withdraw {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, &farm.reward_token, "alice"), alice_reward);
+    assert_eq!(staker_info(&app, &farm, "alice").pending_reward.u128(), 0);
+
+    // Withdrawing again in the same block pays nothing more
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        farm.staking.clone(),
+        &StakingExecuteMsg::Withdraw {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, &farm.reward_token, "alice"), alice_reward);
+}
+
+fn apr(app: &App, farm: &Farm) -> Decimal {
+    let apr: AprResponse = app
+        .query_mock(&farm.staking, &StakingQueryMsg::Apr {})
+        .unwrap();
+    apr.apr
+}
+
+#[test]
+fn apr_prices_the_rewards_with_the_oracle() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let farm = setup_farm(&mut app, &[("alice", 10_000)]);
+    set_reward_per_block(Uint128::new(1));
+    set_blocks_per_year(1_000);
+    bond(&mut app, &farm, "alice", 10_000);
+
+    // 1 * 1_000 reward tokens a year over 10_000 bonded, valued 1:1 without an oracle
+    assert_eq!(apr(&app, &farm), Decimal::percent(10));
+
+    let oracle = instantiate(&mut app, contract_oracle_mock(), &OracleInstantiateMsg {});
+    set_reward_price_oracle(oracle.to_string(), "reward".to_string(), "lp".to_string());
+    // The oracle has no price yet and the query says so
+    assert_err_contains(
+        app.query_mock::<AprResponse, _>(&farm.staking, &StakingQueryMsg::Apr {}),
+        "No price set for reward/lp",
+    );
+
+    set_price(
+        "reward".to_string(),
+        "lp".to_string(),
+        Decimal::percent(250),
+    );
+    assert_eq!(apr(&app, &farm), Decimal::percent(25));
+    // A new oracle price shows up on the next query
+    set_price("reward".to_string(), "lp".to_string(), Decimal::percent(50));
+    assert_eq!(apr(&app, &farm), Decimal::percent(5));
+}
+
+#[test]
+fn undecodable_hook_is_a_parse_error() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let farm = setup_farm(&mut app, &[("alice", 100)]);
+
+    let result = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            farm.lp_token.clone(),
+            &TerraswapExecuteMsg::Send {
+                contract: farm.staking.to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&"stake forever").unwrap(),
+            },
+            &[],
+        )
+        .map_err(|err| err.to_string());
+    assert_err_contains(result.clone(), "Error parsing into type");
+    assert!(!result
+        .unwrap_err()
+        .contains(&MockError::Unauthorized {}.to_string()));
+    assert_eq!(balance(&app, &farm.lp_token, "alice"), 100);
+}
+
+#[test]
+fn unbonding_more_than_bonded_is_an_underflow_error() {
+    let _guard = setup();
+    let mut app = mock_app();
+    let farm = setup_farm(&mut app, &[("alice", 100)]);
+    bond(&mut app, &farm, "alice", 100);
+
+    assert_err_contains(
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            farm.staking.clone(),
+            &StakingExecuteMsg::Unbond {
+                amount: Uint128::new(101),
+            },
+            &[],
+        ),
+        "Cannot Sub with 100 and 101",
+    );
+    assert_eq!(staker_info(&app, &farm, "alice").bond_amount.u128(), 100);
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{assert_err_contains, setup};
+use cosmwasm_std::{Decimal, Uint128};
+use cw_terra_test_mocks::prelude::*;
+use cw_terra_test_mocks::terraswap_mock::{compute_swap, provide_liquidity, PoolState};
+use terraswap::asset::Asset;
+
+// The return of offering `offer` into an offer_pool/ask_pool pool without commission
+fn return_amount(offer_pool: u128, ask_pool: u128, offer: u128) -> u128 {
+    compute_swap(
+        Uint128::new(offer_pool),
+        Uint128::new(ask_pool),
+        Uint128::new(offer),
+        Decimal::zero(),
+    )
+    .unwrap()
+    .return_amount
+    .u128()
+}
+
+// Exact returns of 1.5 (a half), 3.333.. (below a half) and 1.666.. (above a half)
+fn borderline_returns() -> [u128; 3] {
+    [
+        return_amount(1, 3, 1),
+        return_amount(1, 5, 2),
+        return_amount(2, 5, 1),
+    ]
+}
+
+#[test]
+fn floor_truncates_by_default() {
+    let _guard = setup();
+    assert_eq!(get_rounding(), RoundingMode::Floor);
+    assert_eq!(borderline_returns(), [1, 3, 1]);
+}
+
+#[test]
+fn ceil_rounds_up() {
+    let _guard = setup();
+    set_rounding(RoundingMode::Ceil);
+    assert_eq!(borderline_returns(), [2, 4, 2]);
+}
+
+#[test]
+fn nearest_rounds_halves_up() {
+    let _guard = setup();
+    set_rounding(RoundingMode::Nearest);
+    assert_eq!(borderline_returns(), [2, 3, 2]);
+}
+
+#[test]
+fn whole_returns_are_the_same_in_every_mode() {
+    let _guard = setup();
+    // 10 * 10 / (10 + 10) = 5 left in the ask pool
+    for mode in [
+        RoundingMode::Floor,
+        RoundingMode::Ceil,
+        RoundingMode::Nearest,
+    ]
+    .iter()
+    {
+        set_rounding(*mode);
+        assert_eq!(return_amount(10, 10, 10), 5);
+    }
+}
+
+// A pool of the default token/uusd assets nobody has provided to yet
+fn empty_pool() -> PoolState {
+    PoolState {
+        assets: [
+            asset(native("token"), Uint128::zero()),
+            asset(native("uusd"), Uint128::zero()),
+        ],
+        total_share: Share::zero(),
+    }
+}
+
+fn deposits(amount0: u128, amount1: u128) -> [Asset; 2] {
+    [
+        asset(native("token"), Uint128::new(amount0)),
+        asset(native("uusd"), Uint128::new(amount1)),
+    ]
+}
+
+#[test]
+fn first_provide_locks_the_minimum_liquidity() {
+    let _guard = setup();
+    assert_eq!(get_minimum_liquidity(), Uint128::new(1_000));
+    let mut pool = empty_pool();
+
+    // sqrt(4_000_000 * 1_000_000) = 2_000_000, less the 1_000 locked
+    let share = provide_liquidity(&mut pool, &deposits(4_000_000, 1_000_000)).unwrap();
+    assert_eq!(share, Share(Uint128::new(1_999_000)));
+    // The locked shares count towards the supply
+    assert_eq!(pool.total_share, Share(Uint128::new(2_000_000)));
+
+    // Later provides mint pro-rata with nothing locked
+    let share = provide_liquidity(&mut pool, &deposits(400_000, 100_000)).unwrap();
+    assert_eq!(share, Share(Uint128::new(200_000)));
+}
+
+#[test]
+fn minimum_liquidity_is_configurable() {
+    let _guard = setup();
+    set_minimum_liquidity(Uint128::zero());
+    let mut pool = empty_pool();
+    let share = provide_liquidity(&mut pool, &deposits(4_000_000, 1_000_000)).unwrap();
+    assert_eq!(share, Share(Uint128::new(2_000_000)));
+
+    // A first deposit that doesn't cover the lock mints nothing and is refused
+    set_minimum_liquidity(Uint128::new(1_000));
+    let mut pool = empty_pool();
+    assert_err_contains(
+        provide_liquidity(&mut pool, &deposits(1_000, 1_000)),
+        &MockError::DepositTooSmall {}.to_string(),
+    );
+}
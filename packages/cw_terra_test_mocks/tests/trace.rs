@@ -0,0 +1,104 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::{instantiate_pair, mock_app, setup, swap, OWNER};
+use cosmwasm_std::{Addr, Uint128};
+use cw_terra_test_mocks::prelude::*;
+use terra_multi_test::{App, Executor};
+
+// A mint to alice, a transfer from alice to bob and a swap from carol, returning the trace of the pair
+fn trace_of_a_few_ops() -> (App, Vec<String>) {
+    let mut app = mock_app();
+    let pair = instantiate_pair(&mut app);
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        pair.clone(),
+        &TerraswapExecuteMsg::Mint {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(100),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        pair.clone(),
+        &TerraswapExecuteMsg::Transfer {
+            recipient: "bob".to_string(),
+            amount: Uint128::new(40),
+        },
+        &[],
+    )
+    .unwrap();
+    swap(&mut app, &pair, "carol", "uusd", 1_000);
+    let trace: TraceResponse = app.query_mock(&pair, &TerraswapQueryMsg::Trace {}).unwrap();
+    (app, trace.lines)
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn trace_records_every_execute_in_order() {
+    let _guard = setup();
+    let (app, lines) = trace_of_a_few_ops();
+    assert_eq!(lines.len(), 3);
+    let height = format!("height {}:", app.block_info().height);
+    assert!(lines.iter().all(|line| line.starts_with(&height)));
+    assert!(lines[0].contains("Mint") && lines[0].contains("from owner"));
+    assert!(lines[1].contains("Transfer") && lines[1].contains("from alice"));
+    assert!(lines[2].contains("Swap") && lines[2].contains("from carol"));
+}
+
+#[cfg(not(feature = "trace"))]
+#[test]
+fn trace_is_empty_without_the_feature() {
+    let _guard = setup();
+    let (_, lines) = trace_of_a_few_ops();
+    assert!(lines.is_empty());
+}
+
+// Keeps what the mocks log, see the README for showing the output with env_logger instead
+#[cfg(feature = "trace")]
+struct CaptureLogger;
+
+#[cfg(feature = "trace")]
+lazy_static::lazy_static! {
+    static ref LOGGED: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(vec![]);
+}
+
+#[cfg(feature = "trace")]
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == "cw_terra_test_mocks"
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            LOGGED.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(feature = "trace")]
+static CAPTURE_LOGGER: CaptureLogger = CaptureLogger;
+
+#[cfg(feature = "trace")]
+#[test]
+fn handlers_log_every_execute_and_query() {
+    let _guard = setup();
+    // Only this test installs a logger, the others in the file log to nowhere
+    log::set_logger(&CAPTURE_LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let (_, lines) = trace_of_a_few_ops();
+    let logged = LOGGED.lock().unwrap().clone();
+    // Every recorded execute was logged as well, along with the Trace query that read them back
+    for line in &lines {
+        assert!(logged.contains(&format!("execute {}", line)));
+    }
+    assert!(logged
+        .iter()
+        .any(|line| line.starts_with("query height") && line.ends_with("Trace")));
+}
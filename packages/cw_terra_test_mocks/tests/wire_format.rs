@@ -0,0 +1,182 @@
+#![cfg(feature = "terraswap")]
+
+mod common;
+
+use common::setup;
+use cosmwasm_std::{from_binary, to_binary, Binary, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_terra_test_mocks::prelude::*;
+use std::collections::HashSet;
+use terraswap::asset::Asset;
+
+fn assets() -> [Asset; 2] {
+    [
+        asset(native("token"), Uint128::new(100)),
+        asset(token("token0000"), Uint128::new(200)),
+    ]
+}
+
+// One of every variant, with every optional field set so none of them gets skipped
+fn every_execute_msg() -> Vec<TerraswapExecuteMsg> {
+    vec![
+        TerraswapExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "alice".to_string(),
+            amount: Uint128::new(10),
+            msg: to_binary(&PingMsg {
+                payload: "ping".to_string(),
+            })
+            .unwrap(),
+        }),
+        TerraswapExecuteMsg::Mint {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(10),
+        },
+        TerraswapExecuteMsg::Send {
+            contract: "contract".to_string(),
+            amount: Uint128::new(10),
+            msg: Binary::from(b"{}".to_vec()),
+        },
+        TerraswapExecuteMsg::Burn {
+            amount: Uint128::new(10),
+        },
+        TerraswapExecuteMsg::Transfer {
+            recipient: "bob".to_string(),
+            amount: Uint128::new(10),
+        },
+        TerraswapExecuteMsg::TransferFrom {
+            owner: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: Uint128::new(10),
+        },
+        TerraswapExecuteMsg::FreezeAccount {
+            addr: "alice".to_string(),
+        },
+        TerraswapExecuteMsg::UnfreezeAccount {
+            addr: "alice".to_string(),
+        },
+        TerraswapExecuteMsg::ProvideLiquidity { assets: assets() },
+        TerraswapExecuteMsg::WithdrawLiquidity {
+            amount: Share(Uint128::new(10)),
+        },
+        TerraswapExecuteMsg::Swap {
+            offer_asset: asset(native("uusd"), Uint128::new(10)),
+            belief_price: Some(Decimal::percent(101)),
+            max_spread: Some(Decimal::percent(1)),
+            to: Some("bob".to_string()),
+        },
+        TerraswapExecuteMsg::Rebase {
+            factor: Decimal::percent(110),
+        },
+        TerraswapExecuteMsg::ResetVolume {},
+    ]
+}
+
+fn every_query_msg() -> Vec<TerraswapQueryMsg> {
+    let [asset0, asset1] = assets();
+    vec![
+        TerraswapQueryMsg::Pair {
+            asset_infos: Some([asset0.info.clone(), asset1.info.clone()]),
+        },
+        TerraswapQueryMsg::Pool {},
+        TerraswapQueryMsg::Assets {},
+        TerraswapQueryMsg::TokenInfo {},
+        TerraswapQueryMsg::Balance {
+            address: "alice".to_string(),
+        },
+        TerraswapQueryMsg::DetailedBalance {
+            address: "alice".to_string(),
+        },
+        TerraswapQueryMsg::AllAccounts {
+            start_after: Some("alice".to_string()),
+            limit: Some(10),
+        },
+        TerraswapQueryMsg::Capabilities {},
+        TerraswapQueryMsg::Treasury {},
+        TerraswapQueryMsg::Config {},
+        TerraswapQueryMsg::IsFrozen {
+            addr: "alice".to_string(),
+        },
+        TerraswapQueryMsg::Simulation {
+            offer_asset: asset0.clone(),
+        },
+        TerraswapQueryMsg::PriceImpact {
+            offer_asset: asset0.clone(),
+        },
+        TerraswapQueryMsg::ExchangeRate {
+            offer_asset_info: asset0.info.clone(),
+        },
+        TerraswapQueryMsg::SimulationBoth {
+            offer_amount: Uint128::new(10),
+        },
+        TerraswapQueryMsg::ProvideQuote { deposits: assets() },
+        TerraswapQueryMsg::BlockInfo {},
+        TerraswapQueryMsg::Trace {},
+        TerraswapQueryMsg::Volume {},
+        TerraswapQueryMsg::KHistory {
+            start_after: Some(1),
+            limit: Some(10),
+        },
+        TerraswapQueryMsg::Version {},
+    ]
+}
+
+#[test]
+fn every_execute_msg_roundtrips() {
+    let _guard = setup();
+    let msgs = every_execute_msg();
+    // Each variant is listed once
+    let kinds: HashSet<TerraswapExecuteMsgKind> = msgs.iter().map(|msg| msg.kind()).collect();
+    assert_eq!(kinds.len(), msgs.len());
+    for msg in msgs {
+        assert_msg_roundtrips(msg);
+    }
+}
+
+#[test]
+fn every_query_msg_roundtrips() {
+    let _guard = setup();
+    let msgs = every_query_msg();
+    let kinds: HashSet<TerraswapQueryMsgKind> = msgs.iter().map(|msg| msg.kind()).collect();
+    assert_eq!(kinds.len(), msgs.len());
+    for msg in msgs {
+        assert_msg_roundtrips(msg);
+    }
+}
+
+#[test]
+fn sudo_msg_roundtrips() {
+    let _guard = setup();
+    assert_msg_roundtrips(TerraswapSudoMsg::UpdateConfig {
+        commission_rate: Decimal::permille(3),
+    });
+    assert_msg_roundtrips(TerraswapSudoMsg::LoadConfig {
+        config: MockConfig {
+            pool: mock_pool_info(),
+            commission_rate: Decimal::permille(3),
+            commission_split: (30, 0),
+            token_info: mock_token_info(),
+            balances: vec![("alice".to_string(), Uint128::new(10))],
+        },
+    });
+}
+
+#[test]
+fn wire_format_is_pinned() {
+    let _guard = setup();
+    assert_eq!(
+        to_binary(&TerraswapQueryMsg::Pool {}).unwrap(),
+        Binary::from(br#"{"pool":{}}"#.to_vec())
+    );
+    assert_eq!(
+        to_binary(&TerraswapExecuteMsg::Burn {
+            amount: Uint128::new(10)
+        })
+        .unwrap(),
+        Binary::from(br#"{"burn":{"amount":"10"}}"#.to_vec())
+    );
+    // The optional asset_infos of Pair can be left out altogether, like a plain Terraswap Pair {} query
+    assert_eq!(
+        from_binary::<TerraswapQueryMsg>(&Binary::from(br#"{"pair":{}}"#.to_vec())).unwrap(),
+        TerraswapQueryMsg::Pair { asset_infos: None }
+    );
+}